@@ -1,24 +1,97 @@
 use serde::{Deserialize, Serialize};
 use strum::Display;
+use uuid::Uuid;
 
+// The config crate lowercases keys, so modes have to be accepted in lowercase too.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
     #[default]
+    #[serde(alias = "schedule")]
     Schedule,
+    #[serde(alias = "settings")]
     Settings,
+    #[serde(alias = "edit")]
     Edit,
+    #[serde(alias = "search")]
+    Search,
+    #[serde(alias = "palette")]
+    Palette,
+    #[serde(alias = "help")]
+    Help,
+    #[serde(alias = "notifications")]
+    Notifications,
+    #[serde(alias = "unlock")]
+    Unlock,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+}
+
+impl Notification {
+    pub fn new(level: NotificationLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+        }
+    }
 }
 
+// Variants with data are written as tables in config files, whose keys get lowercased.
 #[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
 pub enum Action {
     Tick,
     Render,
+    #[serde(alias = "resize")]
     Resize(u16, u16),
     Suspend,
     Resume,
     Quit,
     ClearScreen,
+    #[serde(alias = "error")]
     Error(String),
+    #[serde(alias = "notify")]
+    Notify(Notification),
+    ToggleNotificationHistory,
+    ReloadConfig,
+    ReloadSchedule,
+    SaveSettings,
+    SaveSchedule,
+    ScheduleReloaded,
+    UnlockSecrets,
+    SecretsUnlocked,
+    LockSecrets,
     Help,
+    #[serde(alias = "changemode")]
     ChangeMode(Mode),
+    OpenCommandPalette,
+    EditSelected,
+    AddConference,
+    OpenSearch,
+    SelectPrev,
+    SelectNext,
+    PrevDay,
+    NextDay,
+    ToggleField,
+    FocusUp,
+    FocusDown,
+    FocusLeft,
+    FocusRight,
+    CloseForm,
+    TogglePasswordVisibility,
+    CopyLink,
+    CopyPassword,
+    JoinSelected,
+    JoinLive,
+    #[serde(alias = "join")]
+    Join(Uuid),
 }
@@ -1,34 +1,73 @@
-use crate::action::Mode;
+use crate::action::{Mode, Notification, NotificationLevel};
 use crate::{
     action::Action,
-    config::Config,
-    entities::{Schedule, Settings},
-    persistence::schedule::ScheduleLoader,
+    autostart::Autostart,
+    clipboard,
+    config::{self, Config, ConfigWatcher},
+    entities::{JoinRecord, Schedule, Settings},
+    instance::{Instance, InstanceKind, InstanceStatus, Message, Response},
+    launcher::{ConferenceLauncher, Launcher},
+    persistence::history::HistoryLoader,
+    persistence::schedule::{self, ScheduleLoader, ScheduleWatcher},
+    persistence::secrets::{SecretBackend, SecretStore, SharedSecretStore},
     persistence::settings::SettingsLoader,
+    theme,
     tui::{Event, Tui},
-    ui::{Component, Home},
+    ui::{Component, Home, Notifications},
+    SCHEDULE_DIR, SCHEDULE_NAME,
 };
+use chrono::Local;
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::prelude::Rect;
 use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// How long the config files have to stay untouched before they are reloaded.
+/// Editors tend to emit several events per save.
+const CONFIG_RELOAD_DELAY: Duration = Duration::from_millis(300);
 
 pub struct App {
     schedule_loader: Box<dyn ScheduleLoader>,
     settings_loader: Box<dyn SettingsLoader>,
+    history_loader: Box<dyn HistoryLoader>,
     schedule: Rc<RefCell<Schedule>>,
     settings: Rc<RefCell<Settings>>,
+    secrets: SharedSecretStore,
+    secrets_dir: PathBuf,
+    // the backend of the store in `secrets`, which differs from the settings while
+    // passwords are waiting to be moved to another store
+    secret_backend: SecretBackend,
+    pending_secret_backend: Option<SecretBackend>,
+    // the previous store, deleted once the passwords are saved to the new one
+    retired_secrets: Option<Box<dyn SecretStore>>,
+    save_after_unlock: bool,
+    history: Rc<RefCell<Vec<JoinRecord>>>,
+    launcher: Box<dyn Launcher>,
+    autostart: Autostart,
+    instance: Instance,
     config: Config,
     tick_rate: f64,
     frame_rate: f64,
+    mouse: bool,
     components: Vec<Box<dyn Component>>,
     should_quit: bool,
     should_suspend: bool,
     mode: Mode,
     last_tick_key_events: Vec<KeyEvent>,
+    config_watcher: Option<ConfigWatcher>,
+    config_reload_requested: Option<Instant>,
+    // picks up changes to the schedule file made by the command line or other instances
+    schedule_watcher: Option<ScheduleWatcher>,
+    schedule_reload_requested: Option<Instant>,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
 }
@@ -37,40 +76,73 @@ impl App {
     pub fn new(
         tick_rate: f64,
         frame_rate: f64,
+        mouse: bool,
+        secrets_dir: PathBuf,
         mut schedule_loader: Box<dyn ScheduleLoader>,
         mut settings_loader: Box<dyn SettingsLoader>,
+        mut history_loader: Box<dyn HistoryLoader>,
     ) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
-        let schedule: Rc<RefCell<Schedule>> =
-            Rc::new(RefCell::new(schedule_loader.load("schedule")));
-        let settings: Rc<RefCell<Settings>> = 
-            Rc::new(RefCell::new(settings_loader.load()));
+        let settings: Rc<RefCell<Settings>> = Rc::new(RefCell::new(settings_loader.load()));
+        let wanted_backend = settings.borrow().secret_store;
+        let secret_backend = wanted_backend.find_existing(&secrets_dir);
+        let secrets: SharedSecretStore = Rc::new(RefCell::new(secret_backend.open(&secrets_dir)));
+        let schedule: Rc<RefCell<Schedule>> = Rc::new(RefCell::new(
+            schedule_loader.load(SCHEDULE_NAME, secrets.borrow_mut().as_mut())?,
+        ));
+        let history = Rc::new(RefCell::new(history_loader.load().unwrap_or_else(|err| {
+            error!("Failed to load the join history: {err}");
+            Vec::new()
+        })));
+        let config = Config::new()?;
+        theme::set_theme(settings.borrow().theme);
+        theme::set_styles(&config.styles);
         Ok(Self {
             schedule_loader,
             tick_rate,
             frame_rate,
+            mouse,
             schedule: Rc::clone(&schedule),
             settings: Rc::clone(&settings),
-            components: vec![Box::new(Home::new(
-                Rc::clone(&schedule),
-                Rc::clone(&settings),
-            ))],
+            secrets: Rc::clone(&secrets),
+            secrets_dir,
+            secret_backend,
+            pending_secret_backend: (secret_backend != wanted_backend).then_some(wanted_backend),
+            retired_secrets: None,
+            save_after_unlock: false,
+            history: Rc::clone(&history),
+            launcher: Box::new(ConferenceLauncher::new(config.config.launcher.clone())),
+            autostart: Autostart::default(),
+            instance: Instance::new(InstanceKind::Interface),
+            components: vec![
+                Box::new(Home::new(
+                    Rc::clone(&schedule),
+                    Rc::clone(&settings),
+                    Rc::clone(&secrets),
+                    history,
+                )),
+                Box::new(Notifications::default()),
+            ],
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Schedule,
             last_tick_key_events: Vec::new(),
+            config_watcher: None,
+            config_reload_requested: None,
+            schedule_watcher: None,
+            schedule_reload_requested: None,
             action_tx,
             action_rx,
             settings_loader,
+            history_loader,
         })
     }
 
     pub async fn run(&mut self) -> Result<()> {
-        let _ = self.schedule; // to appease clippy
-        let _ = self.settings; // same reason
         let mut tui = Tui::new()?
-            // .mouse(true) // uncomment this line to enable mouse support
+            .mouse(self.mouse || self.settings.borrow().mouse)
+            .paste(true)
             .tick_rate(self.tick_rate)
             .frame_rate(self.frame_rate);
         tui.enter()?;
@@ -85,6 +157,43 @@ impl App {
             component.init(tui.size()?)?;
         }
 
+        self.config_watcher =
+            match ConfigWatcher::new(&config::get_config_dir(), self.action_tx.clone()) {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    warn!("Not watching the config directory: {err}");
+                    None
+                }
+            };
+        fs::create_dir_all(SCHEDULE_DIR)?;
+        self.schedule_watcher = match ScheduleWatcher::new(
+            Path::new(SCHEDULE_DIR),
+            &format!("{SCHEDULE_NAME}.json"),
+            self.action_tx.clone(),
+            Action::ReloadSchedule,
+        ) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                warn!("Not watching the schedule: {err}");
+                None
+            }
+        };
+        if !self.take_ownership() {
+            info!("Another instance owns autostart");
+        }
+
+        if let Some(backend) = self.pending_secret_backend.take() {
+            self.switch_secret_store(backend)?;
+        } else if self.secrets.borrow().is_locked()
+            && self
+                .schedule
+                .borrow()
+                .conferences()
+                .any(|conference| conference.password.is_some() || conference.password_id.is_some())
+        {
+            self.action_tx.send(Action::UnlockSecrets)?;
+        }
+
         let action_tx = self.action_tx.clone();
         loop {
             self.handle_events(&mut tui).await?;
@@ -93,7 +202,6 @@ impl App {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
                 action_tx.send(Action::ClearScreen)?;
-                // tui.mouse(true);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -158,14 +266,60 @@ impl App {
             match action.clone() {
                 Action::Tick => {
                     self.last_tick_key_events.drain(..);
+                    if self
+                        .config_reload_requested
+                        .is_some_and(|requested| requested.elapsed() >= CONFIG_RELOAD_DELAY)
+                    {
+                        self.config_reload_requested = None;
+                        self.reload_config()?;
+                    }
+                    if self
+                        .schedule_reload_requested
+                        .is_some_and(|requested| requested.elapsed() >= schedule::RELOAD_DELAY)
+                    {
+                        self.schedule_reload_requested = None;
+                        self.reload_schedule()?;
+                    }
+                    // a daemon may exit while the user interface is open
+                    if self.take_ownership() {
+                        self.handle_instance_requests()?;
+                        self.join_due()?;
+                    }
                 }
+                Action::ReloadConfig => self.config_reload_requested = Some(Instant::now()),
+                Action::ReloadSchedule => self.schedule_reload_requested = Some(Instant::now()),
                 Action::Quit => self.should_quit = true,
                 Action::Suspend => self.should_suspend = true,
                 Action::Resume => self.should_suspend = false,
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
-                Action::ChangeMode(mode) => self.mode = mode,
+                Action::ChangeMode(mode) => {
+                    self.mode = mode;
+                    theme::set_mode(mode);
+                }
+                Action::Error(ref message) => error!("{message}"),
+                Action::SaveSettings => {
+                    let settings = self.settings.borrow().clone();
+                    tui.set_mouse(self.mouse || settings.mouse)?;
+                    let secret_store = settings.secret_store;
+                    if let Err(err) = self.settings_loader.save(settings) {
+                        self.action_tx.send(Action::Notify(Notification::new(
+                            NotificationLevel::Error,
+                            format!("Failed to save settings: {err}"),
+                        )))?;
+                    }
+                    if secret_store != self.secret_backend
+                        && self.pending_secret_backend != Some(secret_store)
+                    {
+                        self.switch_secret_store(secret_store)?;
+                    }
+                }
+                Action::SaveSchedule => self.save_schedule()?,
+                Action::SecretsUnlocked => self.handle_secrets_unlocked()?,
+                Action::LockSecrets => self.lock_secrets()?,
+                Action::Join(id) => self.join(id)?,
+                Action::JoinLive => self.join_live()?,
                 _ => {}
             }
             for component in self.components.iter_mut() {
@@ -177,6 +331,258 @@ impl App {
         Ok(())
     }
 
+    /// Saves the schedule, asking for the passphrase first if a password has to be written
+    /// to the locked vault.
+    fn save_schedule(&mut self) -> Result<()> {
+        let result = self.schedule_loader.save(
+            &mut self.schedule.borrow_mut(),
+            self.secrets.borrow_mut().as_mut(),
+        );
+        match result {
+            Ok(()) => {
+                if let Some(mut retired) = self.retired_secrets.take() {
+                    retired.delete()?;
+                    info!("Passwords moved to the {}", self.secret_backend);
+                }
+            }
+            Err(_) if self.secrets.borrow().is_locked() => {
+                self.save_after_unlock = true;
+                self.action_tx.send(Action::UnlockSecrets)?;
+            }
+            Err(err) => {
+                let message = format!("Failed to save schedule: {err}");
+                error!("{message}");
+                self.action_tx.send(Action::Notify(Notification::new(
+                    NotificationLevel::Error,
+                    message,
+                )))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the passwords to the store of `backend`. They are read from the current store
+    /// and written to the new one, so whichever of them is a vault has to be unlocked.
+    fn switch_secret_store(&mut self, backend: SecretBackend) -> Result<()> {
+        if self.secrets.borrow().is_locked() {
+            self.pending_secret_backend = Some(backend);
+            self.action_tx.send(Action::UnlockSecrets)?;
+            return Ok(());
+        }
+        let store = backend.open(&self.secrets_dir);
+        self.retired_secrets = Some(mem::replace(&mut *self.secrets.borrow_mut(), store));
+        self.secret_backend = backend;
+        self.save_schedule()
+    }
+
+    fn handle_secrets_unlocked(&mut self) -> Result<()> {
+        let result = self.schedule_loader.resolve_passwords(
+            &mut self.schedule.borrow_mut(),
+            self.secrets.borrow_mut().as_mut(),
+        );
+        if let Err(err) = result {
+            self.action_tx.send(Action::Notify(Notification::new(
+                NotificationLevel::Error,
+                format!("Failed to read passwords: {err}"),
+            )))?;
+            // the store could not be read, so passwords are neither moved out of it nor saved
+            // to it until the next unlock
+            return Ok(());
+        }
+        self.action_tx.send(Action::Notify(Notification::new(
+            NotificationLevel::Info,
+            "Password vault unlocked",
+        )))?;
+        if let Some(backend) = self.pending_secret_backend.take() {
+            self.switch_secret_store(backend)?;
+        } else if self.retired_secrets.is_some() || mem::take(&mut self.save_after_unlock) {
+            self.save_schedule()?;
+        }
+        Ok(())
+    }
+
+    fn lock_secrets(&mut self) -> Result<()> {
+        self.secrets.borrow_mut().lock();
+        if !self.secrets.borrow().is_locked() {
+            return Ok(());
+        }
+        for conference in self.schedule.borrow_mut().conferences_mut() {
+            if conference.password_id.is_some() {
+                conference.password = None;
+            }
+        }
+        self.action_tx.send(Action::Notify(Notification::new(
+            NotificationLevel::Info,
+            "Password vault locked",
+        )))?;
+        Ok(())
+    }
+
+    /// Opens the conference, copies its password to the clipboard and records the join.
+    fn join(&mut self, id: Uuid) -> Result<()> {
+        let Some(conference) = self.schedule.borrow().get_conference(id).cloned() else {
+            return Ok(());
+        };
+        if let Err(err) = self.launcher.launch(&conference) {
+            let message = format!("Failed to join {}: {err}", conference.title);
+            error!("{message}");
+            self.action_tx.send(Action::Notify(Notification::new(
+                NotificationLevel::Error,
+                message,
+            )))?;
+            return Ok(());
+        }
+
+        let mut message = format!("Joined {}", conference.title);
+        match (&conference.password, &conference.password_id) {
+            (Some(password), _) => {
+                match clipboard::copy(password, self.config.config.clipboard_command.as_deref()) {
+                    Ok(()) => message.push_str(", password copied"),
+                    Err(err) => warn!("Failed to copy the password: {err}"),
+                }
+            }
+            (None, Some(_)) => message.push_str(", unlock the vault to copy the password"),
+            (None, None) => {}
+        }
+        let record = JoinRecord::new(&conference);
+        if let Err(err) = self.history_loader.append(&record) {
+            error!("Failed to record the join: {err}");
+        }
+        self.history.borrow_mut().push(record);
+        info!("{message}");
+        self.action_tx.send(Action::Notify(Notification::new(
+            NotificationLevel::Info,
+            message,
+        )))?;
+        Ok(())
+    }
+
+    /// Answers the messages of other instances.
+    fn handle_instance_requests(&mut self) -> Result<()> {
+        while let Some(request) = self.instance.try_recv() {
+            let response = match request.message {
+                Message::Reload => self.reload_schedule()?,
+                Message::Show => {
+                    // the terminal bell marks the window as urgent in most window managers
+                    let mut stdout = io::stdout();
+                    stdout.write_all(b"\x07")?;
+                    stdout.flush()?;
+                    self.action_tx.send(Action::Notify(Notification::new(
+                        NotificationLevel::Info,
+                        "Already running here",
+                    )))?;
+                    Response::Done("Shown".into())
+                }
+                Message::Status => Response::Status(InstanceStatus {
+                    kind: InstanceKind::Interface,
+                    pid: std::process::id(),
+                    autostart: self.settings.borrow().autostart,
+                    conferences: self.schedule.borrow().conferences().count(),
+                }),
+            };
+            request.reply(response);
+        }
+        Ok(())
+    }
+
+    /// Re-reads the schedule after another process changed it.
+    fn reload_schedule(&mut self) -> Result<Response> {
+        let result = self
+            .schedule_loader
+            .load(SCHEDULE_NAME, self.secrets.borrow_mut().as_mut());
+        match result {
+            Ok(schedule) => {
+                *self.schedule.borrow_mut() = schedule;
+                self.action_tx.send(Action::ScheduleReloaded)?;
+                info!("Schedule reloaded");
+                Ok(Response::Done("Reloaded".into()))
+            }
+            Err(err) => {
+                error!("Schedule not reloaded: {err}");
+                Ok(Response::Failed(format!("Schedule not reloaded: {err}")))
+            }
+        }
+    }
+
+    /// Takes over autostart if no other process owns it. Returns whether this one does.
+    fn take_ownership(&mut self) -> bool {
+        if self.instance.is_owner() {
+            return true;
+        }
+        if !self.instance.try_take_ownership() {
+            return false;
+        }
+        // the joins of the previous owner are only in the history file
+        match self.history_loader.load() {
+            Ok(history) => self.autostart.mark_joined(
+                &self.schedule.borrow(),
+                &history,
+                Local::now().naive_local(),
+            ),
+            Err(err) => error!("Failed to load the join history: {err}"),
+        }
+        true
+    }
+
+    fn join_due(&mut self) -> Result<()> {
+        let due = self.autostart.due(
+            &self.schedule.borrow(),
+            &self.settings.borrow(),
+            Local::now().naive_local(),
+        );
+        for id in due {
+            self.action_tx.send(Action::Join(id))?;
+        }
+        Ok(())
+    }
+
+    fn join_live(&mut self) -> Result<()> {
+        let live = {
+            let schedule = self.schedule.borrow();
+            schedule
+                .live_at(Local::now().naive_local())
+                .map(|(day, index)| schedule.get_day(day)[index].id)
+        };
+        match live {
+            Some(id) => self.join(id),
+            None => {
+                self.action_tx.send(Action::Notify(Notification::new(
+                    NotificationLevel::Warning,
+                    "No conference is live right now",
+                )))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Re-reads the config files, keeping the current config if they are invalid.
+    fn reload_config(&mut self) -> Result<()> {
+        let config = match Config::new() {
+            Ok(config) => config,
+            Err(err) => {
+                let message = format!("Config not reloaded: {err}");
+                error!("{message}");
+                self.action_tx.send(Action::Notify(Notification::new(
+                    NotificationLevel::Error,
+                    message,
+                )))?;
+                return Ok(());
+            }
+        };
+        theme::set_styles(&config.styles);
+        self.launcher = Box::new(ConferenceLauncher::new(config.config.launcher.clone()));
+        for component in self.components.iter_mut() {
+            component.register_config_handler(config.clone())?;
+        }
+        self.config = config;
+        info!("Config reloaded");
+        self.action_tx.send(Action::Notify(Notification::new(
+            NotificationLevel::Info,
+            "Configuration reloaded",
+        )))?;
+        Ok(())
+    }
+
     fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
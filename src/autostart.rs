@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use uuid::Uuid;
+
+use crate::entities::{JoinRecord, Schedule, Settings, Time};
+
+/// Decides when conferences are joined on their own. A conference that allows autostart is
+/// joined `early_join_minutes` before it starts, or as soon as possible while it is running,
+/// and only once a day.
+#[derive(Default)]
+pub struct Autostart {
+    date: Option<NaiveDate>,
+    // the conferences started on `date`
+    started: HashSet<Uuid>,
+}
+
+impl Autostart {
+    /// Returns the ids of the conferences to join at `now`.
+    pub fn due(
+        &mut self,
+        schedule: &Schedule,
+        settings: &Settings,
+        now: NaiveDateTime,
+    ) -> Vec<Uuid> {
+        self.start_day(now.date());
+        if !settings.autostart {
+            return Vec::new();
+        }
+        let day = now.weekday().num_days_from_monday() as usize;
+        let iso_week = now.iso_week().week();
+        let minute = now.hour() * 60 + now.minute();
+        let minute_of = |time: &Time| u32::from(time.hours()) * 60 + u32::from(time.minutes());
+
+        let mut due = Vec::new();
+        for conference in schedule.get_day(day) {
+            let join_from = minute_of(&conference.start_time)
+                .saturating_sub(u32::from(settings.early_join_minutes));
+            let is_due = conference.autostart_permission
+                && conference.week.includes(iso_week)
+                && join_from <= minute
+                && minute < minute_of(&conference.end_time);
+            if is_due && self.started.insert(conference.id) {
+                due.push(conference.id);
+            }
+        }
+        due
+    }
+
+    /// Counts the conferences joined today according to `history` as started, so that one
+    /// joined by another process before this one took over autostart is not joined again.
+    pub fn mark_joined(&mut self, schedule: &Schedule, history: &[JoinRecord], now: NaiveDateTime) {
+        self.start_day(now.date());
+        let joined_today = history
+            .iter()
+            .filter(|record| record.joined_at.date_naive() == now.date());
+        for record in joined_today {
+            let conferences = schedule.conferences().filter(|c| record.is_for(c));
+            self.started
+                .extend(conferences.map(|conference| conference.id));
+        }
+    }
+
+    fn start_day(&mut self, date: NaiveDate) {
+        if self.date != Some(date) {
+            self.date = Some(date);
+            self.started.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+    use crate::entities::fixtures::{at, conference};
+    use crate::entities::Conference;
+
+    #[test]
+    fn test_due() {
+        let mut schedule = Schedule::new("test".into());
+        // 2024-10-21 is a Monday
+        let standup = Conference {
+            autostart_permission: true,
+            ..conference("Standup", "09:00", "09:15")
+        };
+        let standup_id = standup.id;
+        schedule.add_conference(0, standup);
+        schedule.add_conference(0, conference("Lecture", "10:00", "11:30"));
+        let settings = Settings {
+            autostart: true,
+            early_join_minutes: 5,
+            ..Settings::default()
+        };
+        let mut autostart = Autostart::default();
+
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 08:54")),
+            []
+        );
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 08:55")),
+            [standup_id]
+        );
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 09:01")),
+            []
+        );
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 10:30")),
+            []
+        );
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-28 09:00")),
+            [standup_id]
+        );
+
+        let disabled = Settings {
+            autostart: false,
+            ..settings
+        };
+        assert_eq!(
+            Autostart::default().due(&schedule, &disabled, at("2024-10-21 09:00")),
+            []
+        );
+    }
+
+    #[test]
+    fn test_mark_joined() {
+        let mut schedule = Schedule::new("test".into());
+        let standup = Conference {
+            autostart_permission: true,
+            ..conference("Standup", "09:00", "09:15")
+        };
+        let standup_id = standup.id;
+        let joined_at = |date_time| Local.from_local_datetime(&date_time).unwrap();
+        let mut record = JoinRecord::new(&standup);
+        schedule.add_conference(0, standup);
+        let settings = Settings {
+            autostart: true,
+            ..Settings::default()
+        };
+
+        // joined by another process before this one took over
+        record.joined_at = joined_at(at("2024-10-21 09:00"));
+        let mut autostart = Autostart::default();
+        autostart.mark_joined(&schedule, &[record.clone()], at("2024-10-21 09:05"));
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 09:05")),
+            []
+        );
+
+        record.joined_at = joined_at(at("2024-10-14 09:00"));
+        let mut autostart = Autostart::default();
+        autostart.mark_joined(&schedule, &[record], at("2024-10-21 09:05"));
+        assert_eq!(
+            autostart.due(&schedule, &settings, at("2024-10-21 09:05")),
+            [standup_id]
+        );
+    }
+}
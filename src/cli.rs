@@ -1,6 +1,16 @@
-use clap::Parser;
+use std::path::PathBuf;
 
-use crate::config::{get_config_dir, get_data_dir};
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::{get_config_dir, get_data_dir, DumpFormat};
+use crate::entities::{Time, Week};
+use crate::instance::Message;
+use output::OutputArgs;
+pub use schedule::run_schedule_command;
+use schedule::{parse_day, ConferenceId};
+
+mod output;
+mod schedule;
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -12,6 +22,165 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
     pub frame_rate: f64,
+
+    /// Enable mouse support, even if it is turned off in the settings
+    #[arg(short, long)]
+    pub mouse: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Join conferences on autostart in the background, without the user interface
+    Daemon,
+    /// Send a message to the running instance that owns autostart
+    Instance {
+        #[arg(value_enum)]
+        message: Message,
+    },
+    #[command(flatten)]
+    Schedule(ScheduleCommand),
+}
+
+/// Commands working on the saved schedule without the user interface. Conferences are
+/// addressed by their id as shown by `list`, or any part of it that starts it and is unique.
+#[derive(Subcommand, Debug)]
+pub enum ScheduleCommand {
+    /// List the conferences by day
+    List {
+        /// Only list the conferences of this day, e.g. `mon` or `friday`
+        #[arg(short, long, value_parser = parse_day)]
+        day: Option<usize>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Add a conference
+    Add(AddArgs),
+    /// Change a conference, keeping what is not given
+    Edit {
+        id: ConferenceId,
+        #[command(flatten)]
+        changes: EditArgs,
+    },
+    /// Remove a conference
+    Remove { id: ConferenceId },
+    /// Show the next conference to start
+    Next {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Show the conference running now
+    Now {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Join a conference, the one running now if no id is given
+    Join { id: Option<ConferenceId> },
+}
+
+#[derive(Args, Debug)]
+pub struct AddArgs {
+    /// Day of the week, e.g. `mon` or `friday`
+    #[arg(short, long, value_parser = parse_day)]
+    pub day: usize,
+
+    #[arg(long)]
+    pub title: String,
+
+    /// Start time, as HH:MM
+    #[arg(long, value_parser = Time::parse)]
+    pub start: Time,
+
+    /// End time, as HH:MM
+    #[arg(long, value_parser = Time::parse)]
+    pub end: Time,
+
+    #[arg(long, default_value = "")]
+    pub link: String,
+
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Weeks the conference is held on: Every, Even or Odd
+    #[arg(long, value_parser = Week::parse, default_value = "Every")]
+    pub week: Week,
+
+    /// Allow joining the conference on autostart
+    #[arg(long)]
+    pub autostart: bool,
+
+    /// Comma separated tags
+    #[arg(long, value_delimiter = ',')]
+    pub tags: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct EditArgs {
+    /// Move the conference to another day, e.g. `mon` or `friday`
+    #[arg(short, long, value_parser = parse_day)]
+    pub day: Option<usize>,
+
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Start time, as HH:MM
+    #[arg(long, value_parser = Time::parse)]
+    pub start: Option<Time>,
+
+    /// End time, as HH:MM
+    #[arg(long, value_parser = Time::parse)]
+    pub end: Option<Time>,
+
+    #[arg(long)]
+    pub link: Option<String>,
+
+    /// New password, an empty one removes it
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Weeks the conference is held on: Every, Even or Odd
+    #[arg(long, value_parser = Week::parse)]
+    pub week: Option<Week>,
+
+    /// Whether the conference may be joined on autostart
+    #[arg(long)]
+    pub autostart: Option<bool>,
+
+    /// Comma separated tags, replacing the current ones
+    #[arg(long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Validate a config file, reporting every problem found in it
+    Check {
+        /// Config file to check, defaults to the one in the config directory
+        #[arg(value_name = "FILE")]
+        path: Option<PathBuf>,
+    },
+    /// Print the default config merged with the current one
+    Dump {
+        /// Format to write the config in
+        #[arg(short, long, value_enum, default_value_t)]
+        format: DumpFormat,
+
+        /// Write the config to the config directory instead of stdout
+        #[arg(short, long)]
+        write: bool,
+
+        /// Overwrite an existing config file when writing
+        #[arg(long, requires = "write")]
+        force: bool,
+    },
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -0,0 +1,231 @@
+use std::fmt;
+
+use clap::{Args, ValueEnum};
+use color_eyre::{eyre::eyre, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::schedule::DAY_NAMES;
+use crate::entities::Conference;
+use crate::launcher::{MeetingLink, Platform};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Lines for people to read
+    #[default]
+    Text,
+    /// JSON, `null` when there is no conference
+    Json,
+    /// A line per conference filled in from `--template`
+    Template,
+}
+
+#[derive(Args, Debug)]
+pub struct OutputArgs {
+    /// How to print the conferences
+    #[arg(long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Line printed for each conference with `--format template`. Placeholders are the
+    /// fields of the JSON output, e.g. `{title} in {minutes_until} min`
+    #[arg(long, required_if_eq("format", "template"))]
+    pub template: Option<String>,
+}
+
+impl OutputArgs {
+    /// Prints a single conference, or `none` if there is no conference in text format.
+    pub fn print_one(&self, conference: Option<ConferenceOutput>, none: &str) -> Result<()> {
+        match (self.format, conference) {
+            (OutputFormat::Json, conference) => {
+                println!("{}", serde_json::to_string(&conference)?)
+            }
+            (_, Some(conference)) => self.print_all(&[conference])?,
+            // status bars show an empty line when nothing is on
+            (OutputFormat::Template, None) => println!(),
+            (OutputFormat::Text, None) => println!("{none}"),
+        }
+        Ok(())
+    }
+
+    pub fn print_all(&self, conferences: &[ConferenceOutput]) -> Result<()> {
+        match self.format {
+            OutputFormat::Text => {
+                for conference in conferences {
+                    println!("{conference}");
+                }
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string(conferences)?),
+            OutputFormat::Template => {
+                let template = self.template.as_deref().unwrap_or_default();
+                for conference in conferences {
+                    println!("{}", render_template(template, conference)?);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Length of the ids in text output, enough to tell the conferences of a schedule apart.
+const SHORT_ID_LENGTH: usize = 8;
+
+/// A conference as printed by the schedule commands. Its passwords are left out.
+#[derive(Debug, Serialize)]
+pub struct ConferenceOutput {
+    pub id: String,
+    pub day: &'static str,
+    pub title: String,
+    pub start: String,
+    pub end: String,
+    pub link: String,
+    pub platform: Option<Platform>,
+    pub meeting_id: Option<String>,
+    pub week: &'static str,
+    pub tags: Vec<String>,
+    pub autostart: bool,
+    /// Minutes until the conference starts, for `next`
+    pub minutes_until: Option<i64>,
+    /// Minutes until the conference ends, for `now`
+    pub minutes_left: Option<i64>,
+}
+
+impl ConferenceOutput {
+    pub fn new(day: usize, conference: &Conference) -> Self {
+        let meeting = MeetingLink::parse(&conference.link).ok();
+        Self {
+            id: conference.id.to_string(),
+            day: DAY_NAMES[day],
+            title: conference.title.clone(),
+            start: conference.start_time.to_string(),
+            end: conference.end_time.to_string(),
+            link: conference.link.clone(),
+            platform: meeting.as_ref().map(|meeting| meeting.platform),
+            meeting_id: meeting.and_then(|meeting| meeting.meeting_id),
+            week: conference.week.as_str(),
+            tags: conference.tags.clone(),
+            autostart: conference.autostart_permission,
+            minutes_until: None,
+            minutes_left: None,
+        }
+    }
+}
+
+impl fmt::Display for ConferenceOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}  {}-{}  {}",
+            &self.id[..SHORT_ID_LENGTH],
+            self.start,
+            self.end,
+            self.title
+        )?;
+        if self.week != "Every" {
+            write!(f, "  ({} weeks)", self.week.to_lowercase())?;
+        }
+        if let Some(minutes) = self.minutes_until {
+            write!(f, "  (in {minutes} min)")?;
+        }
+        if let Some(minutes) = self.minutes_left {
+            write!(f, "  ({minutes} min left)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces the `{field}` placeholders in `template` with the fields of `conference`. Missing
+/// values are left empty and list items are separated by `, `.
+fn render_template(template: &str, conference: &ConferenceOutput) -> Result<String> {
+    let value = serde_json::to_value(conference)?;
+    let fields = value
+        .as_object()
+        .expect("a conference should serialize to an object");
+    let mut line = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        let name = &rest[open + 1..close];
+        let value = fields.get(name).ok_or_else(|| {
+            let names: Vec<&str> = fields.keys().map(String::as_str).collect();
+            eyre!(
+                "Unknown placeholder {{{name}}}, the placeholders are {{{}}}",
+                names.join("}, {")
+            )
+        })?;
+        line.push_str(&rest[..open]);
+        line.push_str(&field_text(value));
+        rest = &rest[close + 1..];
+    }
+    line.push_str(rest);
+    Ok(line)
+}
+
+fn field_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(field_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_render_template() -> Result<()> {
+        let conference = Conference {
+            id: "3f2a9c1e-0000-4000-8000-000000000001".parse().unwrap(),
+            title: "Standup".into(),
+            link: "https://zoom.us/j/123".into(),
+            start_time: "09:00".parse().unwrap(),
+            end_time: "09:15".parse().unwrap(),
+            tags: vec!["team".into(), "daily".into()],
+            ..Conference::default()
+        };
+        let mut output = ConferenceOutput::new(0, &conference);
+        output.minutes_until = Some(5);
+
+        assert_eq!(
+            render_template(
+                "{title} ({platform} {meeting_id}) at {start} in {minutes_until} min [{tags}]{minutes_left}",
+                &output
+            )?,
+            "Standup (zoom 123) at 09:00 in 5 min [team, daily]"
+        );
+        assert_eq!(
+            render_template("{id} {day} {", &output)?,
+            "3f2a9c1e-0000-4000-8000-000000000001 Mon {"
+        );
+        assert_eq!(
+            output.to_string(),
+            "3f2a9c1e  09:00-09:15  Standup  (in 5 min)"
+        );
+        assert!(render_template("{password}", &output).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_template_list() -> Result<()> {
+        let mut conference = Conference::default();
+        let output = ConferenceOutput::new(0, &conference);
+        assert_eq!(render_template("[{tags}]", &output)?, "[]");
+
+        conference.tags = vec!["lecture".into()];
+        let output = ConferenceOutput::new(0, &conference);
+        assert_eq!(render_template("[{tags}]", &output)?, "[lecture]");
+
+        conference.tags = vec!["lecture".into(), "math".into(), "online".into()];
+        let output = ConferenceOutput::new(0, &conference);
+        assert_eq!(
+            render_template("[{tags}]", &output)?,
+            "[lecture, math, online]"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+use std::env;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDateTime, Weekday};
+use color_eyre::{eyre::eyre, Result};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::output::{ConferenceOutput, OutputFormat};
+use super::{AddArgs, EditArgs, ScheduleCommand};
+use crate::clipboard;
+use crate::config::{self, Config};
+use crate::entities::{Conference, JoinRecord, Schedule, Settings};
+use crate::instance::{self, Message};
+use crate::launcher::{ConferenceLauncher, Launcher};
+use crate::persistence::history::HistoryLoader;
+use crate::persistence::schedule::ScheduleLoader;
+use crate::persistence::secrets::SecretStore;
+use crate::SCHEDULE_NAME;
+
+pub const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// A conference id as given on the command line, which may be just the start of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConferenceId(String);
+
+impl FromStr for ConferenceId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim().to_lowercase();
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Err(format!(
+                "Invalid conference id `{s}`: must be an id shown by list, e.g. 3f2a9c1e"
+            ));
+        }
+        Ok(Self(id))
+    }
+}
+
+impl ConferenceId {
+    /// Finds the only conference whose id starts with this one.
+    pub fn resolve(&self, schedule: &Schedule) -> Result<Uuid> {
+        let mut matches = schedule
+            .conferences()
+            .map(|conference| conference.id)
+            .filter(|id| id.to_string().starts_with(&self.0));
+        match (matches.next(), matches.next()) {
+            (Some(id), None) => Ok(id),
+            (Some(_), Some(_)) => Err(eyre!(
+                "More than one conference has an id starting with {}, give more of it",
+                self.0
+            )),
+            (None, _) => Err(eyre!("No conference {}", self.0)),
+        }
+    }
+}
+
+/// Parses a day of the week, e.g. `mon` or `Friday`, into its index from Monday.
+pub fn parse_day(s: &str) -> Result<usize, String> {
+    Weekday::from_str(s)
+        .map(|day| day.num_days_from_monday() as usize)
+        .map_err(|_| format!("Invalid day `{s}`: must be a day of the week, e.g. mon"))
+}
+
+pub async fn run_schedule_command(
+    command: ScheduleCommand,
+    schedule_dir: &Path,
+    settings: &Settings,
+    schedule_loader: &mut dyn ScheduleLoader,
+    history_loader: &mut dyn HistoryLoader,
+) -> Result<()> {
+    let mut secrets = settings
+        .secret_store
+        .find_existing(schedule_dir)
+        .open(schedule_dir);
+    unlock_from_env(secrets.as_mut())?;
+    let mut schedule = schedule_loader.load(SCHEDULE_NAME, secrets.as_mut())?;
+    let now = Local::now().naive_local();
+
+    match command {
+        ScheduleCommand::List { day, output } => match output.format {
+            OutputFormat::Text => print_list(&schedule, day),
+            _ => {
+                let conferences: Vec<ConferenceOutput> = (0..DAY_NAMES.len())
+                    .filter(|&listed| day.is_none_or(|day| day == listed))
+                    .flat_map(|day| {
+                        schedule
+                            .get_day(day)
+                            .iter()
+                            .map(move |conference| ConferenceOutput::new(day, conference))
+                    })
+                    .collect();
+                output.print_all(&conferences)?;
+            }
+        },
+        ScheduleCommand::Add(args) => {
+            let (day, conference) = new_conference(args)?;
+            let title = conference.title.clone();
+            schedule.add_conference(day, conference);
+            save(&mut schedule, schedule_loader, secrets.as_mut()).await?;
+            println!("Added {title}");
+        }
+        ScheduleCommand::Edit { id, changes } => {
+            let id = id.resolve(&schedule)?;
+            let (old_day, _) = schedule.position(id).expect("resolved ids should exist");
+            let conference = schedule
+                .remove_conference(id)
+                .expect("resolved ids should exist");
+            let (day, conference) = edit_conference(old_day, conference, changes)?;
+            let title = conference.title.clone();
+            schedule.add_conference(day, conference);
+            save(&mut schedule, schedule_loader, secrets.as_mut()).await?;
+            println!("Changed {title}");
+        }
+        ScheduleCommand::Remove { id } => {
+            let id = id.resolve(&schedule)?;
+            let conference = schedule
+                .remove_conference(id)
+                .expect("resolved ids should exist");
+            save(&mut schedule, schedule_loader, secrets.as_mut()).await?;
+            // only once the schedule no longer refers to it
+            if let Some(password_id) = &conference.password_id {
+                if secrets.is_locked() {
+                    warn!("Password of {} left in the locked vault", conference.title);
+                } else {
+                    secrets.remove(password_id)?;
+                }
+            }
+            println!("Removed {}", conference.title);
+        }
+        ScheduleCommand::Next { output } => {
+            let next = schedule
+                .next_after(now)
+                .map(|(start, day, index)| ConferenceOutput {
+                    minutes_until: Some((start - now).num_minutes()),
+                    ..ConferenceOutput::new(day, &schedule.get_day(day)[index])
+                });
+            output.print_one(next, "No conference is coming up")?;
+        }
+        ScheduleCommand::Now { output } => {
+            let live = schedule.live_at(now).map(|(day, index)| {
+                let conference = &schedule.get_day(day)[index];
+                let end = now.date().and_time((&conference.end_time).into());
+                ConferenceOutput {
+                    minutes_left: Some((end - now).num_minutes()),
+                    ..ConferenceOutput::new(day, conference)
+                }
+            });
+            output.print_one(live, "No conference is live")?;
+        }
+        ScheduleCommand::Join { id } => {
+            let conference = match id {
+                Some(id) => schedule
+                    .get_conference(id.resolve(&schedule)?)
+                    .expect("resolved ids should exist"),
+                None => live(&schedule, now)?,
+            };
+            join(conference, history_loader)?;
+        }
+    }
+    Ok(())
+}
+
+/// Unlocks the vault with the passphrase in the environment, if it is set, as there is no
+/// prompt to ask for it.
+fn unlock_from_env(secrets: &mut dyn SecretStore) -> Result<()> {
+    if !secrets.is_locked() || !secrets.exists() {
+        return Ok(());
+    }
+    if let Ok(passphrase) = env::var(passphrase_env()) {
+        secrets.unlock(&passphrase)?;
+    }
+    Ok(())
+}
+
+fn passphrase_env() -> String {
+    format!("{}_PASSPHRASE", config::PROJECT_NAME.clone())
+}
+
+/// Saves the schedule and asks the running instance, if there is one, to reload it.
+async fn save(
+    schedule: &mut Schedule,
+    schedule_loader: &mut dyn ScheduleLoader,
+    secrets: &mut dyn SecretStore,
+) -> Result<()> {
+    if let Err(err) = schedule_loader.save(schedule, secrets) {
+        if secrets.is_locked() {
+            return Err(eyre!(
+                "The password vault is locked, set {} to its passphrase",
+                passphrase_env()
+            ));
+        }
+        return Err(err);
+    }
+    if instance::send(Message::Reload).await.is_ok() {
+        info!("Asked the running instance to reload the schedule");
+    }
+    Ok(())
+}
+
+fn live(schedule: &Schedule, now: NaiveDateTime) -> Result<&Conference> {
+    let (day, index) = schedule
+        .live_at(now)
+        .ok_or_else(|| eyre!("No conference is live"))?;
+    Ok(&schedule.get_day(day)[index])
+}
+
+fn new_conference(args: AddArgs) -> Result<(usize, Conference)> {
+    let conference = Conference {
+        id: Uuid::new_v4(),
+        title: args.title,
+        link: args.link,
+        start_time: args.start,
+        end_time: args.end,
+        password: args.password.filter(|password| !password.is_empty()),
+        password_id: None,
+        autostart_permission: args.autostart,
+        week: args.week,
+        tags: clean_tags(args.tags),
+    };
+    validate(&conference)?;
+    Ok((args.day, conference))
+}
+
+fn edit_conference(
+    day: usize,
+    conference: Conference,
+    changes: EditArgs,
+) -> Result<(usize, Conference)> {
+    let conference = Conference {
+        title: changes.title.unwrap_or(conference.title),
+        link: changes.link.unwrap_or(conference.link),
+        start_time: changes.start.unwrap_or(conference.start_time),
+        end_time: changes.end.unwrap_or(conference.end_time),
+        password: match changes.password {
+            Some(password) => (!password.is_empty()).then_some(password),
+            None => conference.password,
+        },
+        autostart_permission: changes.autostart.unwrap_or(conference.autostart_permission),
+        week: changes.week.unwrap_or(conference.week),
+        tags: changes.tags.map(clean_tags).unwrap_or(conference.tags),
+        ..conference
+    };
+    validate(&conference)?;
+    Ok((changes.day.unwrap_or(day), conference))
+}
+
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    tags.iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn validate(conference: &Conference) -> Result<()> {
+    if conference.title.trim().is_empty() {
+        return Err(eyre!("The title cannot be empty"));
+    }
+    if conference.start_time >= conference.end_time {
+        return Err(eyre!("The conference has to end after it starts"));
+    }
+    Ok(())
+}
+
+fn print_list(schedule: &Schedule, day: Option<usize>) {
+    // days without conferences are left out, unless asked for
+    let days: Vec<usize> = match day {
+        Some(day) => vec![day],
+        None => (0..DAY_NAMES.len())
+            .filter(|&day| !schedule.get_day(day).is_empty())
+            .collect(),
+    };
+    if days.is_empty() {
+        println!("No conferences");
+    }
+    for day in days {
+        println!("{}", DAY_NAMES[day]);
+        for conference in schedule.get_day(day) {
+            println!("  {}", ConferenceOutput::new(day, conference));
+        }
+    }
+}
+
+/// Opens the conference, copies its password to the clipboard and records the join.
+fn join(conference: &Conference, history_loader: &mut dyn HistoryLoader) -> Result<()> {
+    let config = Config::new()?;
+    ConferenceLauncher::new(config.config.launcher)
+        .launch(conference)
+        .map_err(|err| eyre!("Failed to join {}: {err}", conference.title))?;
+
+    let mut message = format!("Joined {}", conference.title);
+    match (&conference.password, &conference.password_id) {
+        (Some(password), _) => {
+            match clipboard::copy(password, config.config.clipboard_command.as_deref()) {
+                Ok(()) => message.push_str(", password copied"),
+                Err(err) => warn!("Failed to copy the password: {err}"),
+            }
+        }
+        (None, Some(_)) => {
+            message.push_str(&format!(", set {} to copy the password", passphrase_env()))
+        }
+        (None, None) => {}
+    }
+    if let Err(err) = history_loader.append(&JoinRecord::new(conference)) {
+        error!("Failed to record the join: {err}");
+    }
+    println!("{message}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_conference_id() {
+        let mut schedule = Schedule::new("test".into());
+        let ids = [
+            "3f2a9c1e-0000-4000-8000-000000000001",
+            "3f2b0000-0000-4000-8000-000000000002",
+        ]
+        .map(|id| Uuid::parse_str(id).unwrap());
+        for id in ids {
+            schedule.add_conference(
+                0,
+                Conference {
+                    id,
+                    ..Conference::default()
+                },
+            );
+        }
+        let resolve = |id: &str| id.parse::<ConferenceId>().unwrap().resolve(&schedule);
+
+        assert_eq!(resolve("3F2A").unwrap(), ids[0]);
+        assert_eq!(resolve(&ids[1].to_string()).unwrap(), ids[1]);
+        assert!(resolve("3f2").is_err());
+        assert!(resolve("ffff").is_err());
+        assert!("mon:0".parse::<ConferenceId>().is_err());
+        assert!("".parse::<ConferenceId>().is_err());
+    }
+}
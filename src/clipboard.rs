@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use color_eyre::{eyre::eyre, Result};
+use tracing::warn;
+
+/// Copies `text` to the clipboard. `command`, when configured, is run with the text on its
+/// standard input, for terminals that do not support OSC 52. If it fails, the OSC 52 escape
+/// sequence is written to the terminal instead, which also works over SSH.
+pub fn copy(text: &str, command: Option<&str>) -> Result<()> {
+    if let Some(command) = command {
+        match run_command(command, text) {
+            Ok(()) => return Ok(()),
+            Err(err) => warn!("Clipboard command `{command}` failed, using OSC 52: {err}"),
+        }
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", BASE64.encode(text))
+}
+
+fn run_command(command: &str, text: &str) -> Result<()> {
+    let mut words = command.split_whitespace();
+    let program = words.next().ok_or_else(|| eyre!("empty command"))?;
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(text.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(eyre!("exited with {status}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hello"), "\x1b]52;c;aGVsbG8=\x07");
+    }
+
+    #[test]
+    fn test_run_command() {
+        assert!(run_command("cat", "hello").is_ok());
+        assert!(run_command("false", "hello").is_err());
+        assert!(run_command("  ", "hello").is_err());
+    }
+}
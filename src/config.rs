@@ -1,6 +1,10 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    path::PathBuf,
+};
 
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -8,22 +12,53 @@ use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use ratatui::style::{Color, Modifier, Style};
-use serde::{de::Deserializer, Deserialize};
+use serde::{
+    de::{self, Deserializer},
+    ser, Deserialize, Serialize, Serializer,
+};
 use tracing::error;
 
-use crate::{action::Action, action::Mode};
+use crate::{action::Action, action::Mode, launcher::LauncherConfig};
+
+mod check;
+mod dump;
+mod watcher;
+
+pub use check::run_check;
+pub use dump::{run_dump, DumpFormat};
+pub use watcher::ConfigWatcher;
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
-#[derive(Clone, Debug, Deserialize, Default)]
+/// Alternative keymaps that can be layered under the user's bindings with `keymap_preset`.
+const KEYMAP_PRESETS: [(&str, &str); 1] = [("vim", include_str!("../.config/presets/vim.json5"))];
+
+/// User config files looked up in the config directory, in the order they are layered.
+const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+    ("config.json5", config::FileFormat::Json5),
+    ("config.json", config::FileFormat::Json),
+    ("config.yaml", config::FileFormat::Yaml),
+    ("config.toml", config::FileFormat::Toml),
+    ("config.ini", config::FileFormat::Ini),
+];
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct AppConfig {
     #[serde(default)]
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    #[serde(default)]
+    pub keymap_preset: Option<String>,
+    /// Command receiving copied text on its standard input, such as `wl-copy`, used instead
+    /// of the OSC 52 escape sequence when set.
+    #[serde(default)]
+    pub clipboard_command: Option<String>,
+    #[serde(default)]
+    pub launcher: LauncherConfig,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default, flatten)]
     pub config: AppConfig,
@@ -54,15 +89,8 @@ impl Config {
             .set_default("data_dir", data_dir.to_str().unwrap())?
             .set_default("config_dir", config_dir.to_str().unwrap())?;
 
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-            ("config.ini", config::FileFormat::Ini),
-        ];
         let mut found_config = false;
-        for (file, format) in &config_files {
+        for (file, format) in &CONFIG_FILES {
             let source = config::File::from(config_dir.join(file))
                 .format(*format)
                 .required(false);
@@ -77,14 +105,17 @@ impl Config {
 
         let mut cfg: Self = builder.build()?.try_deserialize()?;
 
-        for (mode, default_bindings) in default_config.keybindings.iter() {
-            let user_bindings = cfg.keybindings.entry(*mode).or_default();
-            for (key, cmd) in default_bindings.iter() {
-                user_bindings
-                    .entry(key.clone())
-                    .or_insert_with(|| cmd.clone());
-            }
+        if let Some(name) = cfg.config.keymap_preset.clone() {
+            let (_, preset) = KEYMAP_PRESETS
+                .iter()
+                .find(|(preset_name, _)| *preset_name == name)
+                .ok_or_else(|| {
+                    config::ConfigError::Message(format!("Unknown keymap preset `{name}`"))
+                })?;
+            let preset: Config = json5::from_str(preset).unwrap();
+            cfg.keybindings.merge(&preset.keybindings);
         }
+        cfg.keybindings.merge(&default_config.keybindings);
         for (mode, default_styles) in default_config.styles.iter() {
             let user_styles = cfg.styles.entry(*mode).or_default();
             for (style_key, style) in default_styles.iter() {
@@ -125,28 +156,63 @@ fn project_directory() -> Option<ProjectDirs> {
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
 pub struct KeyBindings(pub HashMap<Mode, HashMap<Vec<KeyEvent>, Action>>);
 
+impl KeyBindings {
+    /// Adds the bindings from `other` that are not already bound.
+    fn merge(&mut self, other: &KeyBindings) {
+        for (mode, other_bindings) in other.iter() {
+            let bindings = self.entry(*mode).or_default();
+            for (key, cmd) in other_bindings.iter() {
+                bindings.entry(key.clone()).or_insert_with(|| cmd.clone());
+            }
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for KeyBindings {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, Action>>::deserialize(deserializer)?;
-
-        let keybindings = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        // Actions are parsed per binding so that errors can name the offending key.
+        let parsed_map =
+            HashMap::<Mode, HashMap<String, serde_json::Value>>::deserialize(deserializer)?;
+
+        let mut keybindings = HashMap::new();
+        for (mode, inner_map) in parsed_map {
+            let mut converted_inner_map = HashMap::new();
+            for (key_str, cmd) in inner_map {
+                let invalid =
+                    |err: String| de::Error::custom(format!("{mode:?} binding `{key_str}`: {err}"));
+                let key_sequence = parse_key_sequence(&key_str).map_err(invalid)?;
+                let cmd = Action::deserialize(cmd).map_err(|err| invalid(err.to_string()))?;
+                converted_inner_map.insert(key_sequence, cmd);
+            }
+            keybindings.insert(mode, converted_inner_map);
+        }
 
         Ok(KeyBindings(keybindings))
     }
 }
 
+impl Serialize for KeyBindings {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sorted so that dumped configs are stable and easy to read. Actions go through JSON
+        // values so that every format writes variants with data as tables (YAML would use tags).
+        let mut bindings: BTreeMap<String, BTreeMap<String, serde_json::Value>> = BTreeMap::new();
+        for (mode, mode_bindings) in self.iter() {
+            let entry = bindings.entry(format!("{mode:?}")).or_default();
+            for (keys, action) in mode_bindings {
+                let action = serde_json::to_value(action).map_err(ser::Error::custom)?;
+                entry.insert(key_sequence_to_string(keys), action);
+            }
+        }
+        bindings.serialize(serializer)
+    }
+}
+
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
@@ -246,7 +312,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
         KeyCode::Delete => "delete",
         KeyCode::Insert => "insert",
         KeyCode::F(c) => {
-            char = format!("f({c})");
+            char = format!("f{c}");
             &char
         }
         KeyCode::Char(' ') => "space",
@@ -291,6 +357,13 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
+pub fn key_sequence_to_string(key_sequence: &[KeyEvent]) -> String {
+    key_sequence
+        .iter()
+        .map(|key_event| format!("<{}>", key_event_to_string(key_event)))
+        .collect()
+}
+
 pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     if raw.chars().filter(|c| *c == '>').count() != raw.chars().filter(|c| *c == '<').count() {
         return Err(format!("Unable to parse `{}`", raw));
@@ -328,26 +401,63 @@ impl<'de> Deserialize<'de> for Styles {
     {
         let parsed_map = HashMap::<Mode, HashMap<String, String>>::deserialize(deserializer)?;
 
-        let styles = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
+        let mut styles = HashMap::new();
+        for (mode, inner_map) in parsed_map {
+            let mut converted_inner_map = HashMap::new();
+            for (key, style) in inner_map {
+                let style = try_parse_style(&style)
+                    .map_err(|err| de::Error::custom(format!("{mode:?} style `{key}`: {err}")))?;
+                converted_inner_map.insert(key, style);
+            }
+            styles.insert(mode, converted_inner_map);
+        }
+
+        Ok(Styles(styles))
+    }
+}
+
+impl Serialize for Styles {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let styles: BTreeMap<String, BTreeMap<&String, String>> = self
+            .iter()
+            .map(|(mode, styles)| {
+                let styles = styles
+                    .iter()
+                    .map(|(key, style)| (key, style_to_string(style)))
                     .collect();
-                (mode, converted_inner_map)
+                (format!("{mode:?}"), styles)
             })
             .collect();
-
-        Ok(Styles(styles))
+        styles.serialize(serializer)
     }
 }
 
-pub fn parse_style(line: &str) -> Style {
+fn split_style(line: &str) -> ((String, Modifier), (String, Modifier)) {
     let (foreground, background) =
         line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
-    let foreground = process_color_string(foreground);
-    let background = process_color_string(&background.replace("on ", ""));
+    (
+        process_color_string(foreground),
+        process_color_string(&background.replace("on ", "")),
+    )
+}
+
+/// Like [`parse_style`], but fails on colors that are not recognised instead of ignoring them.
+pub fn try_parse_style(line: &str) -> Result<Style, String> {
+    let (foreground, background) = split_style(line);
+    for (color, _) in [&foreground, &background] {
+        let color = color.trim();
+        if !color.is_empty() && parse_color(color).is_none() {
+            return Err(format!("unknown color `{color}`"));
+        }
+    }
+    Ok(parse_style(line))
+}
+
+pub fn parse_style(line: &str) -> Style {
+    let (foreground, background) = split_style(line);
 
     let mut style = Style::default();
     if let Some(fg) = parse_color(&foreground.0) {
@@ -360,6 +470,49 @@ pub fn parse_style(line: &str) -> Style {
     style
 }
 
+/// Formats `style` the way [`parse_style`] reads it. Colors that cannot be written in the
+/// config syntax are left out.
+pub fn style_to_string(style: &Style) -> String {
+    let mut line: String = [
+        (Modifier::BOLD, "bold "),
+        (Modifier::UNDERLINED, "underline "),
+        (Modifier::REVERSED, "inverse "),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+    .map(|(_, name)| name)
+    .collect();
+    if let Some(fg) = style.fg.and_then(color_to_string) {
+        line.push_str(&fg);
+    }
+    if let Some(bg) = style.bg.and_then(color_to_string) {
+        if !line.is_empty() && !line.ends_with(' ') {
+            line.push(' ');
+        }
+        line.push_str("on ");
+        line.push_str(&bg);
+    }
+    line
+}
+
+fn color_to_string(color: Color) -> Option<String> {
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let Color::Indexed(c) = color else {
+        return None;
+    };
+    Some(match c {
+        0..=7 => NAMES[c as usize].to_string(),
+        16..=231 => {
+            let c = c - 16;
+            format!("rgb{}{}{}", c / 36, c / 6 % 6, c % 6)
+        }
+        232.. => format!("gray{}", c - 232),
+        _ => format!("color{c}"),
+    })
+}
+
 fn process_color_string(color_str: &str) -> (String, Modifier) {
     let color = color_str
         .replace("grey", "gray")
@@ -387,27 +540,26 @@ fn parse_color(s: &str) -> Option<Color> {
     let s = s.trim_end();
     if s.contains("bright color") {
         let s = s.trim_start_matches("bright ");
-        let c = s
-            .trim_start_matches("color")
-            .parse::<u8>()
-            .unwrap_or_default();
+        let c = s.trim_start_matches("color").parse::<u8>().ok()?;
         Some(Color::Indexed(c.wrapping_shl(8)))
     } else if s.contains("color") {
-        let c = s
-            .trim_start_matches("color")
-            .parse::<u8>()
-            .unwrap_or_default();
+        let c = s.trim_start_matches("color").parse::<u8>().ok()?;
         Some(Color::Indexed(c))
     } else if s.contains("gray") {
-        let c = 232
-            + s.trim_start_matches("gray")
-                .parse::<u8>()
-                .unwrap_or_default();
-        Some(Color::Indexed(c))
-    } else if s.contains("rgb") {
-        let red = (s.as_bytes()[3] as char).to_digit(10).unwrap_or_default() as u8;
-        let green = (s.as_bytes()[4] as char).to_digit(10).unwrap_or_default() as u8;
-        let blue = (s.as_bytes()[5] as char).to_digit(10).unwrap_or_default() as u8;
+        let shade = match s.trim_start_matches("gray") {
+            "" => 0,
+            shade => shade.parse::<u8>().ok().filter(|shade| *shade < 24)?,
+        };
+        Some(Color::Indexed(232 + shade))
+    } else if let Some(rgb) = s.strip_prefix("rgb") {
+        // each component is a digit from 0 to 5 in the 6x6x6 color cube
+        let digits = rgb
+            .chars()
+            .map(|c| c.to_digit(6).map(|d| d as u8))
+            .collect::<Option<Vec<_>>>()?;
+        let [red, green, blue] = digits[..] else {
+            return None;
+        };
         let c = 16 + red * 36 + green * 6 + blue;
         Some(Color::Indexed(c))
     } else if s == "bold black" {
@@ -500,6 +652,55 @@ mod tests {
         assert_eq!(color, None);
     }
 
+    #[test]
+    fn test_parse_color_malformed() {
+        assert_eq!(parse_color("rgb"), None);
+        assert_eq!(parse_color("rgb196"), None);
+        assert_eq!(parse_color("colorx"), None);
+        assert_eq!(parse_color("gray30"), None);
+    }
+
+    #[test]
+    fn test_try_parse_style() {
+        assert_eq!(
+            try_parse_style("bold red on blue"),
+            Ok(parse_style("bold red on blue"))
+        );
+        assert!(try_parse_style("red on bleu").is_err());
+        assert!(try_parse_style("purple").is_err());
+    }
+
+    #[test]
+    fn test_invalid_bindings_name_mode_and_key() {
+        let err = json5::from_str::<Config>(
+            r#"{ keybindings: { "Schedule": { "<ctrl-xx>": "Quit" } } }"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Schedule binding `<ctrl-xx>`"), "{err}");
+        let err =
+            json5::from_str::<Config>(r#"{ keybindings: { "Settings": { "<q>": "Qiut" } } }"#)
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("Settings binding `<q>`"), "{err}");
+    }
+
+    #[test]
+    fn test_style_to_string_round_trip() {
+        for line in [
+            "",
+            "red",
+            "on blue",
+            "bold underline red on blue",
+            "inverse rgb123 on gray5",
+            "color12",
+            "bold ",
+        ] {
+            let style = parse_style(line);
+            assert_eq!(parse_style(&style_to_string(&style)), style, "{line}");
+        }
+    }
+
     #[test]
     fn test_config() -> Result<()> {
         let c = Config::new()?;
@@ -514,6 +715,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_default_page_bindings() -> Result<()> {
+        let c = Config::new()?;
+        let schedule = c.keybindings.get(&Mode::Schedule).unwrap();
+        assert_eq!(
+            schedule.get(&parse_key_sequence("<e>").unwrap()),
+            Some(&Action::EditSelected)
+        );
+        assert_eq!(
+            schedule.get(&parse_key_sequence("<right>").unwrap()),
+            Some(&Action::NextDay)
+        );
+        let shift_j = KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(schedule.get(&vec![shift_j]), Some(&Action::JoinLive));
+        assert_eq!(schedule.get(&parse_key_sequence("<j>").unwrap()), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keymap_presets() {
+        for (name, preset) in KEYMAP_PRESETS {
+            let preset: Config = json5::from_str(preset)
+                .unwrap_or_else(|err| panic!("preset `{name}` failed to parse: {err}"));
+            assert!(!preset.keybindings.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_bindings() {
+        let key = parse_key_sequence("<j>").unwrap();
+        let mut bindings = KeyBindings::default();
+        bindings
+            .entry(Mode::Schedule)
+            .or_default()
+            .insert(key.clone(), Action::NextDay);
+        let mut other = KeyBindings::default();
+        other
+            .entry(Mode::Schedule)
+            .or_default()
+            .insert(key.clone(), Action::SelectNext);
+
+        bindings.merge(&other);
+
+        assert_eq!(bindings[&Mode::Schedule][&key], Action::NextDay);
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
@@ -0,0 +1,335 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    process,
+};
+
+use color_eyre::{eyre::eyre, Result};
+use crossterm::event::KeyEvent;
+use ratatui::style::Style;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    get_config_dir, key_sequence_to_string, parse_key_sequence, try_parse_style, Config, CONFIG,
+    CONFIG_FILES, KEYMAP_PRESETS,
+};
+use crate::action::{Action, Mode};
+use crate::launcher::{Platform, PASSWORD_ENV};
+use crate::theme;
+
+const KNOWN_SETTINGS: [&str; 7] = [
+    "data_dir",
+    "config_dir",
+    "keymap_preset",
+    "clipboard_command",
+    "launcher",
+    "keybindings",
+    "styles",
+];
+
+/// A problem found in a config file, located by its dotted key path.
+pub struct Problem {
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Validates the config file at `path` (or the one in the config directory), prints what is
+/// wrong with it and exits with a non-zero status if anything is.
+pub fn run_check(path: Option<PathBuf>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => find_config_file()?,
+    };
+    let problems = check_file(&path)?;
+    if problems.is_empty() {
+        println!("{}: OK", path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{}: {problem}", path.display());
+    }
+    println!("{} problem(s) found", problems.len());
+    process::exit(1);
+}
+
+fn find_config_file() -> Result<PathBuf> {
+    let config_dir = get_config_dir();
+    CONFIG_FILES
+        .iter()
+        .map(|(file, _)| config_dir.join(file))
+        .find(|path| path.exists())
+        .ok_or_else(|| eyre!("No configuration file found in {}", config_dir.display()))
+}
+
+pub fn check_file(path: &Path) -> Result<Vec<Problem>> {
+    let value: Value = config::Config::builder()
+        .add_source(config::File::from(path))
+        .build()?
+        .try_deserialize()?;
+    let Value::Object(settings) = value else {
+        return Err(eyre!("{} does not contain a table", path.display()));
+    };
+
+    let mut problems = Vec::new();
+    for key in settings.keys() {
+        if !KNOWN_SETTINGS.contains(&key.as_str()) {
+            problems.push(Problem {
+                location: key.clone(),
+                message: "unknown setting".into(),
+            });
+        }
+    }
+    let preset = settings
+        .get("keymap_preset")
+        .and_then(Value::as_str)
+        .and_then(|name| {
+            let preset = KEYMAP_PRESETS.iter().find(|(preset, _)| *preset == name);
+            if preset.is_none() {
+                problems.push(Problem {
+                    location: "keymap_preset".into(),
+                    message: format!("unknown keymap preset `{name}`"),
+                });
+            }
+            preset
+        });
+    if let Some(keybindings) = settings.get("keybindings") {
+        check_keybindings(
+            keybindings,
+            preset.map(|(_, preset)| *preset),
+            &mut problems,
+        );
+    }
+    if let Some(styles) = settings.get("styles") {
+        check_styles(styles, &mut problems);
+    }
+    if let Some(launcher) = settings.get("launcher") {
+        check_launcher(launcher, &mut problems);
+    }
+    Ok(problems)
+}
+
+fn check_keybindings(keybindings: &Value, preset: Option<&str>, problems: &mut Vec<Problem>) {
+    let mut inherited: Config = json5::from_str(CONFIG).unwrap();
+    if let Some(preset) = preset {
+        let preset: Config = json5::from_str(preset).unwrap();
+        inherited.keybindings.merge(&preset.keybindings);
+    }
+
+    for (mode_name, bindings) in entries(keybindings, "keybindings", problems) {
+        let location = format!("keybindings.{mode_name}");
+        let Some(mode) = parse_mode(mode_name, &location, problems) else {
+            continue;
+        };
+        let mut bound: HashMap<Vec<KeyEvent>, &str> = HashMap::new();
+        for (key_str, action) in entries(bindings, &location, problems) {
+            let location = format!("{location}.{key_str}");
+            if let Err(err) = Action::deserialize(action) {
+                problems.push(Problem {
+                    location: location.clone(),
+                    message: format!("invalid action: {err}"),
+                });
+            }
+            match parse_key_sequence(key_str) {
+                // spellings such as `<hyphen>` and `<minus>`, or modifiers in another order,
+                // give the same keys
+                Ok(sequence) => {
+                    if let Some(other) = bound.insert(sequence, key_str) {
+                        problems.push(Problem {
+                            location,
+                            message: format!("duplicates the binding for `{other}`"),
+                        });
+                    }
+                }
+                Err(err) => problems.push(Problem {
+                    location,
+                    message: err,
+                }),
+            }
+        }
+
+        // The app dispatches a key as soon as it is bound on its own, so longer sequences
+        // starting with a bound key can never be triggered.
+        let inherited = inherited.keybindings.get(&mode);
+        for (sequence, key_str) in &bound {
+            let shadowing = (1..sequence.len())
+                .map(|len| &sequence[..len])
+                .find(|prefix| {
+                    bound.contains_key(*prefix)
+                        || inherited.is_some_and(|bindings| bindings.contains_key(*prefix))
+                });
+            if let Some(prefix) = shadowing {
+                problems.push(Problem {
+                    location: format!("{location}.{key_str}"),
+                    message: format!(
+                        "is shadowed by the binding for `{}`",
+                        key_sequence_to_string(prefix)
+                    ),
+                });
+            }
+        }
+    }
+}
+
+fn check_styles(styles: &Value, problems: &mut Vec<Problem>) {
+    for (mode_name, mode_styles) in entries(styles, "styles", problems) {
+        let location = format!("styles.{mode_name}");
+        parse_mode(mode_name, &location, problems);
+        for (key, style) in entries(mode_styles, &location, problems) {
+            let location = format!("{location}.{key}");
+            let mut theme = theme::DARK;
+            if !theme.set(key, Style::new()) {
+                problems.push(Problem {
+                    location: location.clone(),
+                    message: "unknown style".into(),
+                });
+            }
+            let Some(style) = style.as_str() else {
+                problems.push(Problem {
+                    location,
+                    message: "expected a style string".into(),
+                });
+                continue;
+            };
+            if let Err(err) = try_parse_style(style) {
+                problems.push(Problem {
+                    location,
+                    message: err,
+                });
+            }
+        }
+    }
+}
+
+fn check_launcher(launcher: &Value, problems: &mut Vec<Problem>) {
+    for (key, value) in entries(launcher, "launcher", problems) {
+        let location = format!("launcher.{key}");
+        match key.as_str() {
+            "native_links" if !value.is_boolean() => problems.push(Problem {
+                location,
+                message: "expected true or false".into(),
+            }),
+            "native_links" => {}
+            "commands" => {
+                for (platform, command) in entries(value, &location, problems) {
+                    let location = format!("{location}.{platform}");
+                    if Platform::deserialize(Value::String(platform.clone())).is_err() {
+                        problems.push(Problem {
+                            location: location.clone(),
+                            message: format!("unknown platform `{platform}`"),
+                        });
+                    }
+                    if command
+                        .as_str()
+                        .is_none_or(|command| command.trim().is_empty())
+                    {
+                        problems.push(Problem {
+                            location,
+                            message: "expected a command".into(),
+                        });
+                    } else if command.as_str().is_some_and(|c| c.contains("{password}")) {
+                        problems.push(Problem {
+                            location,
+                            message: format!(
+                                "`{{password}}` is not replaced, read the password from ${}",
+                                PASSWORD_ENV.as_str()
+                            ),
+                        });
+                    }
+                }
+            }
+            _ => problems.push(Problem {
+                location,
+                message: "unknown setting".into(),
+            }),
+        }
+    }
+}
+
+/// Returns the entries of `value`, reporting it if it is not a table.
+fn entries<'a>(
+    value: &'a Value,
+    location: &str,
+    problems: &mut Vec<Problem>,
+) -> Vec<(&'a String, &'a Value)> {
+    match value {
+        Value::Object(map) => map.iter().collect(),
+        _ => {
+            problems.push(Problem {
+                location: location.into(),
+                message: "expected a table".into(),
+            });
+            Vec::new()
+        }
+    }
+}
+
+fn parse_mode(name: &str, location: &str, problems: &mut Vec<Problem>) -> Option<Mode> {
+    let mode = Mode::deserialize(Value::String(name.into())).ok();
+    if mode.is_none() {
+        problems.push(Problem {
+            location: location.into(),
+            message: format!("unknown mode `{name}`"),
+        });
+    }
+    mode
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_check_reports_problems() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config.json5");
+        fs::write(
+            &path,
+            r#"{
+                colour: "red",
+                keybindings: {
+                    Schedule: {
+                        "<q>": "Qiut",
+                        "<hyphen>": "Quit",
+                        "<minus>": "Quit",
+                        "<e><e>": "EditSelected",
+                    },
+                    Agenda: {},
+                },
+                styles: { Schedule: { status_bar: "purple", title: "red" } },
+                launcher: {
+                    commands: { zoom: "zoom {url}", skype: "skype {url}", teams: "teams {password}" },
+                },
+            }"#,
+        )?;
+
+        let problems = check_file(&path)?;
+
+        let mut locations: Vec<String> = problems.iter().map(|p| p.location.clone()).collect();
+        locations.sort();
+        assert_eq!(
+            locations,
+            [
+                "colour",
+                "keybindings.agenda",
+                "keybindings.schedule.<e><e>",
+                "keybindings.schedule.<minus>",
+                "keybindings.schedule.<q>",
+                "launcher.commands.skype",
+                "launcher.commands.teams",
+                "styles.schedule.status_bar",
+                "styles.schedule.title",
+            ]
+        );
+        Ok(())
+    }
+}
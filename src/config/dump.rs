@@ -0,0 +1,79 @@
+use std::fs;
+
+use clap::ValueEnum;
+use color_eyre::{eyre::eyre, Result};
+use strum::Display;
+
+use super::{get_config_dir, Config};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum DumpFormat {
+    #[default]
+    Json5,
+    Toml,
+    Yaml,
+}
+
+impl DumpFormat {
+    fn file_name(&self) -> String {
+        format!("config.{self}")
+    }
+
+    fn serialize(&self, config: &Config) -> Result<String> {
+        Ok(match self {
+            // plain JSON is valid JSON5, and serde_json can pretty print it
+            DumpFormat::Json5 => serde_json::to_string_pretty(config)?,
+            DumpFormat::Toml => toml::to_string_pretty(config)?,
+            DumpFormat::Yaml => serde_yaml::to_string(config)?,
+        })
+    }
+}
+
+/// Prints the default config merged with the user's, or writes it to the config directory
+/// when `write` is set. An existing file is only replaced with `force`.
+pub fn run_dump(format: DumpFormat, write: bool, force: bool) -> Result<()> {
+    let config = Config::new()?;
+    let contents = format.serialize(&config)?;
+    if !write {
+        print!("{contents}");
+        return Ok(());
+    }
+
+    let config_dir = get_config_dir();
+    let path = config_dir.join(format.file_name());
+    if path.exists() && !force {
+        return Err(eyre!(
+            "{} already exists, pass --force to overwrite it",
+            path.display()
+        ));
+    }
+    fs::create_dir_all(&config_dir)?;
+    fs::write(&path, contents)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CONFIG_FILES;
+
+    #[test]
+    fn test_dump_formats_read_back() -> Result<()> {
+        let config = Config::new()?;
+        for format in [DumpFormat::Json5, DumpFormat::Toml, DumpFormat::Yaml] {
+            let (_, file_format) = CONFIG_FILES
+                .into_iter()
+                .find(|(file, _)| *file == format.file_name())
+                .expect("every dump format should be a config file format");
+            let contents = format.serialize(&config)?;
+            let dumped: Config = config::Config::builder()
+                .add_source(config::File::from_str(&contents, file_format))
+                .build()?
+                .try_deserialize()?;
+            assert_eq!(dumped.keybindings.0, config.keybindings.0, "{format}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+
+use color_eyre::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+use crate::action::Action;
+
+/// Watches the config directory and requests a reload whenever a config file changes. The
+/// directory is created if it does not exist yet, so that a config written later is picked up.
+pub struct ConfigWatcher {
+    // kept alive for as long as the directory should be watched
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    pub fn new(config_dir: &Path, action_tx: UnboundedSender<Action>) -> Result<Self> {
+        fs::create_dir_all(config_dir)?;
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) if is_config_change(&event) => {
+                    let _ = action_tx.send(Action::ReloadConfig);
+                }
+                Ok(_) => {}
+                Err(err) => error!("Config watcher error: {err}"),
+            })?;
+        watcher.watch(config_dir, RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn is_config_change(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|path| {
+        path.file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("config"))
+    })
+}
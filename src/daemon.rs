@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use color_eyre::Result;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::autostart::Autostart;
+use crate::config::Config;
+use crate::entities::{JoinRecord, Schedule, Settings};
+use crate::instance::{Instance, InstanceKind, InstanceStatus, Message, Response};
+use crate::launcher::{ConferenceLauncher, Launcher};
+use crate::persistence::history::HistoryLoader;
+use crate::persistence::schedule::{self, ScheduleLoader, ScheduleWatcher};
+use crate::persistence::secrets::{SecretBackend, SecretStore};
+use crate::persistence::settings::SettingsLoader;
+use crate::SCHEDULE_NAME;
+
+/// How often the daemon looks for conferences to join and for schedule changes.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Joins conferences on autostart without the user interface, e.g. as a service started
+/// with the session. It logs to the log file instead of notifying.
+pub struct Daemon {
+    schedule_loader: Box<dyn ScheduleLoader>,
+    settings_loader: Box<dyn SettingsLoader>,
+    history_loader: Box<dyn HistoryLoader>,
+    schedule: Schedule,
+    settings: Settings,
+    secrets: Box<dyn SecretStore>,
+    /// Backend of `secrets`, which is not the configured one while the passwords are still
+    /// in the store of another.
+    secret_backend: SecretBackend,
+    schedule_dir: PathBuf,
+    launcher: Box<dyn Launcher>,
+    autostart: Autostart,
+}
+
+impl Daemon {
+    pub fn new(
+        schedule_dir: PathBuf,
+        mut schedule_loader: Box<dyn ScheduleLoader>,
+        mut settings_loader: Box<dyn SettingsLoader>,
+        history_loader: Box<dyn HistoryLoader>,
+    ) -> Result<Self> {
+        let settings = settings_loader.load();
+        let secret_backend = settings.secret_store.find_existing(&schedule_dir);
+        let mut secrets = Self::open_secrets(secret_backend, &schedule_dir);
+        let schedule = schedule_loader.load(SCHEDULE_NAME, secrets.as_mut())?;
+        let config = Config::new()?;
+        Ok(Self {
+            schedule_loader,
+            settings_loader,
+            history_loader,
+            schedule,
+            settings,
+            secrets,
+            secret_backend,
+            schedule_dir,
+            launcher: Box::new(ConferenceLauncher::new(config.config.launcher)),
+            autostart: Autostart::default(),
+        })
+    }
+
+    /// Runs until SIGTERM or SIGINT. SIGHUP reloads the settings, the config and the schedule.
+    /// Conferences are only joined while no other instance owns autostart.
+    pub async fn run(&mut self) -> Result<()> {
+        let mut instance = Instance::new(InstanceKind::Daemon);
+        if !self.take_ownership(&mut instance) {
+            info!("Another instance owns autostart, waiting for it to exit");
+        }
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut hangup = signal(SignalKind::hangup())?;
+
+        let (change_tx, mut change_rx) = mpsc::unbounded_channel();
+        fs::create_dir_all(&self.schedule_dir)?;
+        let _watcher = match ScheduleWatcher::new(
+            &self.schedule_dir,
+            &format!("{SCHEDULE_NAME}.json"),
+            change_tx,
+            (),
+        ) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                warn!("Not watching the schedule: {err}");
+                None
+            }
+        };
+        let mut reload_requested: Option<Instant> = None;
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        info!("Daemon started");
+        loop {
+            tokio::select! {
+                _ = terminate.recv() => break,
+                _ = interrupt.recv() => break,
+                _ = hangup.recv() => self.reload_all(),
+                Some(()) = change_rx.recv() => reload_requested = Some(Instant::now()),
+                Some(request) = instance.recv() => {
+                    let response = self.answer(request.message);
+                    request.reply(response);
+                }
+                _ = interval.tick() => {
+                    if reload_requested
+                        .is_some_and(|requested| requested.elapsed() >= schedule::RELOAD_DELAY)
+                    {
+                        reload_requested = None;
+                        self.reload_schedule();
+                    }
+                    if self.take_ownership(&mut instance) {
+                        self.join_due();
+                    }
+                }
+            }
+        }
+        info!("Daemon stopped");
+        Ok(())
+    }
+
+    /// Opens the secret store in use. A vault stays locked, as there is nobody to ask for the
+    /// passphrase.
+    fn open_secrets(backend: SecretBackend, schedule_dir: &Path) -> Box<dyn SecretStore> {
+        let secrets = backend.open(schedule_dir);
+        if secrets.is_locked() && secrets.exists() {
+            info!("Password vault is locked, conferences are joined without their passwords");
+        }
+        secrets
+    }
+
+    /// Takes over autostart if no other process owns it. Returns whether this one does.
+    fn take_ownership(&mut self, instance: &mut Instance) -> bool {
+        if instance.is_owner() {
+            return true;
+        }
+        if !instance.try_take_ownership() {
+            return false;
+        }
+        match self.history_loader.load() {
+            Ok(history) => {
+                let now = Local::now().naive_local();
+                self.autostart.mark_joined(&self.schedule, &history, now);
+            }
+            Err(err) => error!("Failed to load the join history: {err}"),
+        }
+        true
+    }
+
+    fn join_due(&mut self) {
+        let now = Local::now().naive_local();
+        for id in self.autostart.due(&self.schedule, &self.settings, now) {
+            let Some(conference) = self.schedule.get_conference(id) else {
+                continue;
+            };
+            if let Err(err) = self.launcher.launch(conference) {
+                error!("Failed to join {}: {err}", conference.title);
+                continue;
+            }
+            info!("Joined {}", conference.title);
+            if let Err(err) = self.history_loader.append(&JoinRecord::new(conference)) {
+                error!("Failed to record the join: {err}");
+            }
+        }
+    }
+
+    fn answer(&mut self, message: Message) -> Response {
+        match message {
+            Message::Reload => {
+                self.reload_all();
+                Response::Done("Reloaded".into())
+            }
+            Message::Show => Response::Failed("The daemon has no user interface".into()),
+            Message::Status => Response::Status(InstanceStatus {
+                kind: InstanceKind::Daemon,
+                pid: std::process::id(),
+                autostart: self.settings.autostart,
+                conferences: self.schedule.conferences().count(),
+            }),
+        }
+    }
+
+    /// Re-reads the schedule, keeping the current one if it cannot be read.
+    fn reload_schedule(&mut self) {
+        match self
+            .schedule_loader
+            .load(SCHEDULE_NAME, self.secrets.as_mut())
+        {
+            Ok(schedule) => {
+                self.schedule = schedule;
+                info!("Schedule reloaded");
+            }
+            Err(err) => error!("Schedule not reloaded: {err}"),
+        }
+    }
+
+    fn reload_all(&mut self) {
+        self.settings = self.settings_loader.load();
+        // opening the store again would lock an unlocked vault
+        let backend = self.settings.secret_store.find_existing(&self.schedule_dir);
+        if backend != self.secret_backend {
+            self.secrets = Self::open_secrets(backend, &self.schedule_dir);
+            self.secret_backend = backend;
+        }
+        match Config::new() {
+            Ok(config) => {
+                self.launcher = Box::new(ConferenceLauncher::new(config.config.launcher));
+            }
+            Err(err) => error!("Config not reloaded: {err}"),
+        }
+        self.reload_schedule();
+    }
+}
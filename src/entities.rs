@@ -1,10 +1,14 @@
 mod conference;
+#[cfg(test)]
+pub mod fixtures;
+mod join_record;
 mod schedule;
 mod settings;
 mod time;
 
 pub use conference::Conference;
 pub use conference::Week;
+pub use join_record::JoinRecord;
 pub use schedule::Schedule;
 pub use settings::Settings;
 pub use time::Time;
@@ -1,8 +1,9 @@
 use super::Time;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub enum Week {
     #[default]
     Every,
@@ -22,6 +23,23 @@ impl Week {
         }
     }
 
+    /// Whether a conference held on this kind of week takes place in ISO week `iso_week`.
+    pub fn includes(&self, iso_week: u32) -> bool {
+        match self {
+            Week::Every => true,
+            Week::Even => iso_week.is_multiple_of(2),
+            Week::Odd => !iso_week.is_multiple_of(2),
+        }
+    }
+
+    /// Whether two conferences held on these kinds of weeks can fall on the same week.
+    pub fn intersects(&self, other: &Week) -> bool {
+        !matches!(
+            (self, other),
+            (Week::Even, Week::Odd) | (Week::Odd, Week::Even)
+        )
+    }
+
     pub fn parse(s: &str) -> Result<Week, &'static str> {
         match s {
             "Every" => Ok(Week::Every),
@@ -39,13 +57,74 @@ impl FromStr for Week {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Conference {
+    /// Stays the same for the life of the conference, unlike its position in the schedule.
+    /// Schedules saved before conferences had ids read as nil ids, see
+    /// [`super::Schedule::assign_missing_ids`].
+    #[serde(default)]
+    pub id: Uuid,
     pub title: String,
     pub link: String,
     pub start_time: Time,
     pub end_time: Time,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
+    /// Id of the password in the secret store. The schedule file only keeps this id, while
+    /// `password` holds the password once it has been read from the store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_id: Option<String>,
     pub autostart_permission: bool,
     pub week: Week,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Default for Conference {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            title: String::new(),
+            link: String::new(),
+            start_time: Time::default(),
+            end_time: Time::default(),
+            password: None,
+            password_id: None,
+            autostart_permission: false,
+            week: Week::default(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl Conference {
+    /// Whether the two conferences, assumed to be on the same day, overlap in time.
+    pub fn overlaps(&self, other: &Conference) -> bool {
+        self.week.intersects(&other.week)
+            && self.start_time < other.end_time
+            && other.start_time < self.end_time
+    }
+
+    /// Whether the conference is running at `time` during ISO week `iso_week`.
+    pub fn is_live(&self, time: &Time, iso_week: u32) -> bool {
+        self.week.includes(iso_week) && self.start_time <= *time && *time < self.end_time
+    }
+
+    /// Returns the host part of the conference link, e.g. `zoom.us` for
+    /// `https://zoom.us/j/123`.
+    pub fn link_host(&self) -> Option<&str> {
+        let without_scheme = self
+            .link
+            .split_once("://")
+            .map_or(self.link.as_str(), |(_, rest)| rest);
+        let authority = without_scheme
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default();
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let host = host.split(':').next().unwrap_or_default();
+        (!host.is_empty()).then_some(host)
+    }
+}
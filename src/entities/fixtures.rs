@@ -0,0 +1,20 @@
+//! Conferences and times for the tests of the schedule and what works with it.
+
+use chrono::NaiveDateTime;
+
+use super::Conference;
+
+/// A weekly conference without a link or password, which does not allow autostart.
+pub fn conference(title: &str, start: &str, end: &str) -> Conference {
+    Conference {
+        title: title.into(),
+        start_time: start.parse().unwrap(),
+        end_time: end.parse().unwrap(),
+        ..Conference::default()
+    }
+}
+
+/// Parses a local date and time like `2024-10-21 09:00`.
+pub fn at(date_time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap()
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Conference;
+
+/// An entry of the join history, written whenever a conference is opened.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JoinRecord {
+    /// Missing from records written before conferences had ids.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conference_id: Option<Uuid>,
+    pub title: String,
+    pub link: String,
+    pub joined_at: DateTime<Local>,
+}
+
+impl JoinRecord {
+    pub fn new(conference: &Conference) -> Self {
+        Self {
+            conference_id: Some(conference.id),
+            title: conference.title.clone(),
+            link: conference.link.clone(),
+            joined_at: Local::now(),
+        }
+    }
+
+    /// Whether the record is about `conference`.
+    pub fn is_for(&self, conference: &Conference) -> bool {
+        match self.conference_id {
+            Some(id) => id == conference.id,
+            None => self.title == conference.title && self.link == conference.link,
+        }
+    }
+}
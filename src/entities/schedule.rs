@@ -1,5 +1,12 @@
-use super::Conference;
+use super::{Conference, Time};
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+/// How far ahead to look for the next conference, two weeks to cover even and odd ones.
+const LOOKAHEAD_DAYS: u64 = 14;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Schedule {
     pub name: String,
     schedule: [Vec<Conference>; 7],
@@ -14,35 +21,80 @@ impl Schedule {
     }
 
     pub fn get_day(&self, day: usize) -> &Vec<Conference> {
-        assert!(day <= 7 && day > 0);
-        &self.schedule[day - 1]
+        assert!(day < 7);
+        &self.schedule[day]
     }
 
-    pub fn update_conference(
-        &mut self,
-        day: usize,
-        index: usize,
-        conference: Conference,
-    ) -> Result<(), String> {
-        assert!(day <= 7 && day > 0);
+    pub fn get_conference(&self, id: Uuid) -> Option<&Conference> {
+        self.conferences().find(|conference| conference.id == id)
+    }
 
-        *self.schedule[day - 1]
-            .get_mut(index)
-            .ok_or("Conference not found")? = conference;
+    /// Returns the day of the conference with `id` and where it is on that day.
+    pub fn position(&self, id: Uuid) -> Option<(usize, usize)> {
+        self.schedule
+            .iter()
+            .enumerate()
+            .find_map(|(day, conferences)| {
+                let index = conferences
+                    .iter()
+                    .position(|conference| conference.id == id)?;
+                Some((day, index))
+            })
+    }
+
+    /// Replaces the conference with the same id, keeping it on its day.
+    pub fn update_conference(&mut self, conference: Conference) -> Result<(), String> {
+        let (day, index) = self.position(conference.id).ok_or("Conference not found")?;
+        self.schedule[day][index] = conference;
 
         self.sort_conferences();
         Ok(())
     }
 
     pub fn add_conference(&mut self, day: usize, conference: Conference) {
-        assert!(day <= 7 && day > 0);
-        self.schedule[day - 1].push(conference);
+        assert!(day < 7);
+        self.schedule[day].push(conference);
         self.sort_conferences();
     }
 
-    pub fn remove_conference(&mut self, day: usize, index: usize) {
-        assert!(day <= 7 && day > 0);
-        self.schedule[day - 1].remove(index);
+    pub fn remove_conference(&mut self, id: Uuid) -> Option<Conference> {
+        let (day, index) = self.position(id)?;
+        Some(self.schedule[day].remove(index))
+    }
+
+    /// Gives an id to the conferences saved before conferences had them, returning whether
+    /// there were any.
+    pub fn assign_missing_ids(&mut self) -> bool {
+        let mut assigned = false;
+        for conference in self.conferences_mut().filter(|c| c.id.is_nil()) {
+            conference.id = Uuid::new_v4();
+            assigned = true;
+        }
+        assigned
+    }
+
+    /// Replaces this schedule by `other`, a version of `base` changed elsewhere, with the
+    /// changes made here since `base` applied on top. A conference changed on both sides keeps
+    /// the changes made here.
+    pub fn merge(&mut self, base: &Schedule, other: Schedule) {
+        let mut merged = other;
+        merged.name = self.name.clone();
+        for conference in base.conferences() {
+            if self.get_conference(conference.id).is_none() {
+                merged.remove_conference(conference.id);
+            }
+        }
+        for (day, conferences) in self.schedule.iter().enumerate() {
+            for conference in conferences {
+                let unchanged = base.position(conference.id).map(|(day, _)| day) == Some(day)
+                    && base.get_conference(conference.id) == Some(conference);
+                if !unchanged {
+                    merged.remove_conference(conference.id);
+                    merged.add_conference(day, conference.clone());
+                }
+            }
+        }
+        *self = merged;
     }
 
     fn sort_conferences(&mut self) {
@@ -51,7 +103,173 @@ impl Schedule {
         }
     }
 
+    pub fn conferences(&self) -> impl Iterator<Item = &Conference> {
+        self.schedule.iter().flatten()
+    }
+
+    pub fn conferences_mut(&mut self) -> impl Iterator<Item = &mut Conference> {
+        self.schedule.iter_mut().flatten()
+    }
+
     pub fn get_conference_count_by_day(&self) -> Vec<usize> {
         self.schedule.iter().map(|day| day.len()).collect()
     }
+
+    /// Returns the day and index of the conference running at `now`.
+    pub fn live_at(&self, now: NaiveDateTime) -> Option<(usize, usize)> {
+        let day = now.weekday().num_days_from_monday() as usize;
+        let time = Time::from(now.time());
+        let iso_week = now.iso_week().week();
+        let index = self.schedule[day]
+            .iter()
+            .position(|conference| conference.is_live(&time, iso_week))?;
+        Some((day, index))
+    }
+
+    /// Returns the day and index of the next conference to start after `now`, with when it
+    /// starts.
+    pub fn next_after(&self, now: NaiveDateTime) -> Option<(NaiveDateTime, usize, usize)> {
+        (0..LOOKAHEAD_DAYS).find_map(|offset| {
+            let date = now.date().checked_add_days(Days::new(offset))?;
+            let day = date.weekday().num_days_from_monday() as usize;
+            let iso_week = date.iso_week().week();
+            self.schedule[day]
+                .iter()
+                .enumerate()
+                .filter(|(_, conference)| conference.week.includes(iso_week))
+                .map(|(index, conference)| {
+                    (
+                        date.and_time(NaiveTime::from(&conference.start_time)),
+                        index,
+                    )
+                })
+                .find(|(start, _)| *start > now)
+                .map(|(start, index)| (start, day, index))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::entities::fixtures::{at, conference};
+    use crate::entities::Week;
+
+    #[test]
+    fn test_live_and_next() {
+        let mut schedule = Schedule::new("test".into());
+        // 2024-10-21 is the Monday of ISO week 43
+        schedule.add_conference(0, conference("Standup", "09:00", "09:15"));
+        schedule.add_conference(
+            2,
+            Conference {
+                week: Week::Even,
+                ..conference("Seminar", "14:00", "15:30")
+            },
+        );
+
+        assert_eq!(schedule.live_at(at("2024-10-21 09:10")), Some((0, 0)));
+        assert_eq!(schedule.live_at(at("2024-10-21 09:15")), None);
+        assert_eq!(
+            schedule.next_after(at("2024-10-21 09:00")),
+            Some((at("2024-10-28 09:00"), 0, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at("2024-10-27 12:00")),
+            Some((at("2024-10-28 09:00"), 0, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at("2024-10-28 10:00")),
+            Some((at("2024-10-30 14:00"), 2, 0))
+        );
+        assert_eq!(
+            Schedule::new("empty".into()).next_after(at("2024-10-21 09:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ids_survive_sorting() {
+        let mut schedule = Schedule::new("test".into());
+        let lecture = conference("Lecture", "10:00", "11:30");
+        let id = lecture.id;
+        schedule.add_conference(3, lecture);
+        schedule.add_conference(3, conference("Standup", "09:00", "09:15"));
+        assert_eq!(schedule.position(id), Some((3, 1)));
+
+        let mut moved = schedule.get_conference(id).unwrap().clone();
+        moved.start_time = "08:00".parse().unwrap();
+        schedule.update_conference(moved).unwrap();
+        assert_eq!(schedule.position(id), Some((3, 0)));
+
+        assert_eq!(schedule.remove_conference(id).unwrap().title, "Lecture");
+        assert!(schedule.get_conference(id).is_none());
+        assert!(schedule.remove_conference(id).is_none());
+        assert!(schedule
+            .update_conference(conference("Lecture", "10:00", "11:30"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = Schedule::new("test".into());
+        base.add_conference(0, conference("Standup", "09:00", "09:15"));
+        base.add_conference(1, conference("Lecture", "10:00", "11:30"));
+        base.add_conference(
+            2,
+            Conference {
+                week: Week::Even,
+                ..conference("Seminar", "14:00", "15:30")
+            },
+        );
+        let standup = base.get_day(0)[0].id;
+        let lecture = base.get_day(1)[0].id;
+        let seminar = base.get_day(2)[0].id;
+
+        let mut here = base.clone();
+        let mut moved = here.remove_conference(lecture).unwrap();
+        moved.title = "Moved lecture".into();
+        here.add_conference(4, moved);
+        here.remove_conference(seminar);
+        here.add_conference(5, conference("Exam", "08:00", "10:00"));
+
+        let mut elsewhere = base.clone();
+        let mut renamed = elsewhere.get_conference(standup).unwrap().clone();
+        renamed.title = "Daily".into();
+        elsewhere.update_conference(renamed).unwrap();
+        let mut renamed = elsewhere.get_conference(lecture).unwrap().clone();
+        renamed.title = "Renamed lecture".into();
+        elsewhere.update_conference(renamed).unwrap();
+        elsewhere.add_conference(
+            2,
+            Conference {
+                week: Week::Odd,
+                ..conference("Office hours", "16:00", "17:00")
+            },
+        );
+
+        here.merge(&base, elsewhere);
+        let titles: Vec<Vec<&str>> = (0..7)
+            .map(|day| {
+                here.get_day(day)
+                    .iter()
+                    .map(|conference| conference.title.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                vec!["Daily"],
+                vec![],
+                vec!["Office hours"],
+                vec![],
+                vec!["Moved lecture"],
+                vec!["Exam"],
+                vec![],
+            ]
+        );
+    }
 }
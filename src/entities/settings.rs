@@ -1,7 +1,15 @@
+use crate::persistence::secrets::SecretBackend;
+use crate::theme::ThemeName;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct Settings {
     pub autostart: bool,
     pub early_join_minutes: u16,
+    #[serde(default)]
+    pub theme: ThemeName,
+    #[serde(default)]
+    pub mouse: bool,
+    #[serde(default)]
+    pub secret_store: SecretBackend,
 }
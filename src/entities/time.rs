@@ -1,8 +1,9 @@
+use chrono::{Local, NaiveTime, Timelike};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Time(u8, u8);
 
 impl Time {
@@ -37,6 +38,11 @@ impl Time {
         }
     }
 
+    /// Returns the current local time of day.
+    pub fn now() -> Time {
+        Local::now().time().into()
+    }
+
     pub fn hours(&self) -> u8 {
         self.0
     }
@@ -46,6 +52,19 @@ impl Time {
     }
 }
 
+impl From<NaiveTime> for Time {
+    fn from(time: NaiveTime) -> Self {
+        Self(time.hour() as u8, time.minute() as u8)
+    }
+}
+
+impl From<&Time> for NaiveTime {
+    fn from(time: &Time) -> Self {
+        NaiveTime::from_hms_opt(time.0.into(), time.1.into(), 0)
+            .expect("Time should hold a valid time of day")
+    }
+}
+
 impl fmt::Display for Time {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:02}:{:02}", self.0, self.1)
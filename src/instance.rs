@@ -0,0 +1,229 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config;
+use server::IpcServer;
+pub use server::Request;
+
+mod server;
+
+const LOCK_FILE: &str = "instance.lock";
+const SOCKET_FILE: &str = "instance.sock";
+
+/// What one instance can ask the instance that owns autostart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Message {
+    /// Re-read the schedule, after another process changed it
+    Reload,
+    /// Bring the user interface to the user's attention
+    Show,
+    /// Report who is running
+    Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Response {
+    Done(String),
+    Status(InstanceStatus),
+    Failed(String),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum InstanceKind {
+    #[strum(serialize = "user interface")]
+    Interface,
+    #[strum(serialize = "daemon")]
+    Daemon,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceStatus {
+    pub kind: InstanceKind,
+    pub pid: u32,
+    pub autostart: bool,
+    pub conferences: usize,
+}
+
+impl fmt::Display for InstanceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The {} is running (pid {}) with {} conferences, autostart is {}",
+            self.kind,
+            self.pid,
+            self.conferences,
+            if self.autostart { "on" } else { "off" }
+        )
+    }
+}
+
+/// This process' claim on autostart. Only the process holding the lock file in the data
+/// directory joins conferences on its own, and it answers the others on a Unix socket next
+/// to it.
+pub struct Instance {
+    kind: InstanceKind,
+    dir: PathBuf,
+    // the locked file, released when the process exits
+    lock: Option<File>,
+    server: Option<IpcServer>,
+    requests: Option<mpsc::UnboundedReceiver<Request>>,
+}
+
+impl Instance {
+    pub fn new(kind: InstanceKind) -> Self {
+        Self::in_dir(kind, config::get_data_dir())
+    }
+
+    fn in_dir(kind: InstanceKind, dir: PathBuf) -> Self {
+        Self {
+            kind,
+            dir,
+            lock: None,
+            server: None,
+            requests: None,
+        }
+    }
+
+    pub fn is_owner(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// Takes the lock if no other process holds it and starts answering messages. Returns
+    /// whether this process owns autostart now.
+    pub fn try_take_ownership(&mut self) -> bool {
+        if self.is_owner() {
+            return true;
+        }
+        match try_lock(&self.dir.join(LOCK_FILE)) {
+            Ok(Some(lock)) => self.lock = Some(lock),
+            Ok(None) => return false,
+            Err(err) => {
+                warn!("Failed to take the instance lock: {err}");
+                return false;
+            }
+        }
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
+        match IpcServer::bind(self.dir.join(SOCKET_FILE), request_tx) {
+            Ok(server) => {
+                self.server = Some(server);
+                self.requests = Some(request_rx);
+            }
+            Err(err) => warn!("Not listening for other instances: {err}"),
+        }
+        info!("The {} owns autostart", self.kind);
+        true
+    }
+
+    /// Returns a message received from another instance, if there is one.
+    pub fn try_recv(&mut self) -> Option<Request> {
+        self.requests.as_mut()?.try_recv().ok()
+    }
+
+    /// Waits for a message from another instance, forever if this one does not own autostart.
+    pub async fn recv(&mut self) -> Option<Request> {
+        match &mut self.requests {
+            Some(requests) => requests.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+/// Locks the file at `path` without waiting, returning `None` if another process holds it.
+fn try_lock(path: &Path) -> Result<Option<File>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    // SAFETY: the descriptor belongs to `file`, which is open for the whole call
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = std::io::Error::last_os_error();
+        return match err.kind() {
+            ErrorKind::WouldBlock => Ok(None),
+            _ => Err(err.into()),
+        };
+    }
+    file.set_len(0)?;
+    writeln!(file, "{}", std::process::id())?;
+    Ok(Some(file))
+}
+
+/// Sends `message` to the instance that owns autostart, failing if none is running.
+pub async fn send(message: Message) -> Result<Response> {
+    send_to(&config::get_data_dir().join(SOCKET_FILE), message).await
+}
+
+async fn send_to(socket: &Path, message: Message) -> Result<Response> {
+    let stream = UnixStream::connect(socket)
+        .await
+        .map_err(|_| eyre!("No instance is running"))?;
+    let (reader, mut writer) = stream.into_split();
+    writer.write_all(format!("{message}\n").as_bytes()).await?;
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Prints the answer of the running instance to `message`.
+pub async fn run_send(message: Message) -> Result<()> {
+    match send(message).await? {
+        Response::Done(message) => println!("{message}"),
+        Response::Status(status) => println!("{status}"),
+        Response::Failed(message) => return Err(eyre!(message)),
+    }
+    Ok(())
+}
+
+/// Returns the status of the instance that owns autostart, if one is running.
+pub async fn find_running() -> Option<InstanceStatus> {
+    match send(Message::Status).await {
+        Ok(Response::Status(status)) => Some(status),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_owner() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut owner = Instance::in_dir(InstanceKind::Daemon, dir.path().to_path_buf());
+        let mut other = Instance::in_dir(InstanceKind::Interface, dir.path().to_path_buf());
+        assert!(owner.try_take_ownership());
+        assert!(!other.try_take_ownership());
+
+        let answer = tokio::spawn(async move {
+            let request = owner.recv().await.unwrap();
+            assert_eq!(request.message, Message::Reload);
+            request.reply(Response::Done("Reloaded".into()));
+            owner
+        });
+        let response = send_to(&dir.path().join(SOCKET_FILE), Message::Reload).await?;
+        assert_eq!(response, Response::Done("Reloaded".into()));
+
+        drop(answer.await?);
+        assert!(other.try_take_ownership());
+        drop(other);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use color_eyre::Result;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use super::{Message, Response};
+
+/// A message from another instance, waiting for the owner to reply.
+pub struct Request {
+    pub message: Message,
+    reply: oneshot::Sender<Response>,
+}
+
+impl Request {
+    /// Answers the instance that sent the message.
+    pub fn reply(self, response: Response) {
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Accepts connections on the instance socket and hands their messages to the owner.
+pub struct IpcServer {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl IpcServer {
+    /// Binds the socket at `path`, replacing the one left behind by a previous owner.
+    pub fn bind(path: PathBuf, request_tx: mpsc::UnboundedSender<Request>) -> Result<Self> {
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(handle_connection(stream, request_tx.clone()));
+                    }
+                    Err(err) => warn!("Failed to accept a connection: {err}"),
+                }
+            }
+        });
+        Ok(Self { path, task })
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+async fn handle_connection(stream: UnixStream, request_tx: mpsc::UnboundedSender<Request>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    if BufReader::new(reader).read_line(&mut line).await.is_err() {
+        return;
+    }
+    let response = match Message::from_str(line.trim()) {
+        Ok(message) => {
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let request = Request {
+                message,
+                reply: reply_tx,
+            };
+            match request_tx.send(request) {
+                Ok(()) => reply_rx
+                    .await
+                    .unwrap_or_else(|_| Response::Failed("The instance is exiting".into())),
+                Err(_) => Response::Failed("The instance is exiting".into()),
+            }
+        }
+        Err(_) => Response::Failed(format!("Unknown message `{}`", line.trim())),
+    };
+    if let Ok(response) = serde_json::to_string(&response) {
+        let _ = writer.write_all(format!("{response}\n").as_bytes()).await;
+    }
+}
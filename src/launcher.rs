@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config;
+use crate::entities::Conference;
+
+mod meeting;
+mod platform;
+
+pub use meeting::MeetingLink;
+pub use platform::Platform;
+
+lazy_static::lazy_static! {
+    /// Environment variable holding the conference password for launcher commands.
+    pub static ref PASSWORD_ENV: String =
+        format!("{}_CONFERENCE_PASSWORD", config::PROJECT_NAME.clone());
+}
+
+/// How conferences are opened, set under `launcher` in the config file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LauncherConfig {
+    /// Rewrite links to the URL scheme of the platform's desktop app where there is one,
+    /// e.g. `zoommtg://` for Zoom. These links may include the conference password, which
+    /// other users can then read from the arguments of the process opening them.
+    #[serde(default)]
+    pub native_links: bool,
+    /// Commands run instead of opening the link in the browser, by platform name (`zoom`,
+    /// `meet`, `teams`, `jitsi`, `webex` or `other`). `{url}` is replaced by the link and
+    /// `{id}` by the meeting id. The conference password is not put in the arguments, which
+    /// other users can read from the process list, but passed in the environment variable
+    /// named by [`PASSWORD_ENV`], `MYSCHEDULE_TUI_CONFERENCE_PASSWORD`.
+    #[serde(default)]
+    pub commands: BTreeMap<Platform, String>,
+}
+
+/// Opens conferences for the user to join.
+pub trait Launcher {
+    fn launch(&self, conference: &Conference) -> Result<()>;
+}
+
+/// Opens conferences with the command configured for their platform, or in the default
+/// browser.
+pub struct ConferenceLauncher {
+    config: LauncherConfig,
+}
+
+impl ConferenceLauncher {
+    pub fn new(config: LauncherConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Launcher for ConferenceLauncher {
+    fn launch(&self, conference: &Conference) -> Result<()> {
+        if conference.link.is_empty() {
+            return Err(eyre!("{} has no link", conference.title));
+        }
+        let url = Url::parse(&conference.link)
+            .map_err(|err| eyre!("invalid link `{}`: {err}", conference.link))?;
+        let meeting = MeetingLink::from_url(&url);
+        let password = conference.password.as_deref();
+        let link = self
+            .config
+            .native_links
+            .then(|| meeting.platform.native_url(&url, password))
+            .flatten()
+            .unwrap_or_else(|| conference.link.clone());
+
+        match self.config.commands.get(&meeting.platform) {
+            Some(template) => run_command(
+                &expand_template(template, &link, meeting.meeting_id.as_deref()),
+                password,
+            ),
+            None => Ok(webbrowser::open(&link)?),
+        }
+    }
+}
+
+/// Splits the command template into arguments and fills in the placeholders. The template is
+/// not passed to a shell, so values do not need quoting. There is no placeholder for the
+/// password, see [`LauncherConfig::commands`].
+fn expand_template(template: &str, url: &str, meeting_id: Option<&str>) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{url}", url)
+                .replace("{id}", meeting_id.unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Runs the command with the conference password in the environment variable named by
+/// [`PASSWORD_ENV`], if there is one.
+fn run_command(args: &[String], password: Option<&str>) -> Result<()> {
+    let (program, args) = args.split_first().ok_or_else(|| eyre!("empty command"))?;
+    let mut command = Command::new(program);
+    match password {
+        Some(password) => command.env(PASSWORD_ENV.as_str(), password),
+        None => command.env_remove(PASSWORD_ENV.as_str()),
+    };
+    let mut child = command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| eyre!("failed to run `{program}`: {err}"))?;
+    // the app keeps running after the conference is opened, reap it when it exits
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(
+            expand_template(
+                "zoom --url={url}  --id {id}",
+                "zoommtg://zoom.us/join?confno=1",
+                Some("1"),
+            ),
+            ["zoom", "--url=zoommtg://zoom.us/join?confno=1", "--id", "1"]
+        );
+        assert_eq!(
+            expand_template("open {url} {id}", "https://x", None),
+            ["open", "https://x", ""]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use url::Url;
+
+use super::Platform;
+
+/// What can be read from a conference link: the platform, the meeting id and a passcode
+/// embedded in the link, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeetingLink {
+    pub platform: Platform,
+    pub meeting_id: Option<String>,
+    pub passcode: Option<String>,
+}
+
+impl MeetingLink {
+    /// Parses `link`, failing if it is not an absolute URL.
+    pub fn parse(link: &str) -> Result<MeetingLink, url::ParseError> {
+        Ok(Self::from_url(&Url::parse(link.trim())?))
+    }
+
+    pub fn from_url(url: &Url) -> MeetingLink {
+        let platform = Platform::detect(url);
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default();
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.into_owned())
+                .filter(|value| !value.is_empty())
+        };
+        // the segment following one of `markers`, e.g. the id in `/j/<id>`
+        let after = |markers: &[&str]| {
+            segments
+                .iter()
+                .position(|segment| markers.contains(segment))
+                .and_then(|index| segments.get(index + 1))
+                .map(|segment| decode(segment))
+        };
+
+        let (meeting_id, passcode) = match platform {
+            Platform::Zoom => (after(&["j", "w", "join"]), query("pwd")),
+            Platform::Meet | Platform::Jitsi => (segments.first().map(|id| decode(id)), None),
+            Platform::Teams => (after(&["meet", "meetup-join"]), query("p")),
+            Platform::Webex => (after(&["meet", "join"]).or(query("MTID")), None),
+            Platform::Other => (None, None),
+        };
+        MeetingLink {
+            platform,
+            meeting_id,
+            passcode,
+        }
+    }
+}
+
+fn decode(segment: &str) -> String {
+    url::form_urlencoded::parse(format!("id={segment}").as_bytes())
+        .next()
+        .map_or_else(|| segment.to_string(), |(_, value)| value.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn meeting(platform: Platform, id: Option<&str>, passcode: Option<&str>) -> MeetingLink {
+        MeetingLink {
+            platform,
+            meeting_id: id.map(String::from),
+            passcode: passcode.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for (link, expected) in [
+            (
+                "https://us02web.zoom.us/j/85412345678?pwd=abc123",
+                meeting(Platform::Zoom, Some("85412345678"), Some("abc123")),
+            ),
+            (
+                "https://zoom.us/wc/join/85412345678",
+                meeting(Platform::Zoom, Some("85412345678"), None),
+            ),
+            (
+                "https://meet.google.com/abc-defg-hij?authuser=0",
+                meeting(Platform::Meet, Some("abc-defg-hij"), None),
+            ),
+            (
+                "https://teams.live.com/meet/9312345678?p=Xy7Abc",
+                meeting(Platform::Teams, Some("9312345678"), Some("Xy7Abc")),
+            ),
+            (
+                "https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc%40thread.v2/0",
+                meeting(Platform::Teams, Some("19:meeting_abc@thread.v2"), None),
+            ),
+            (
+                "https://meet.jit.si/WeeklyStandup",
+                meeting(Platform::Jitsi, Some("WeeklyStandup"), None),
+            ),
+            (
+                "https://company.webex.com/meet/jdoe",
+                meeting(Platform::Webex, Some("jdoe"), None),
+            ),
+            (
+                "https://example.com/room/1",
+                meeting(Platform::Other, None, None),
+            ),
+        ] {
+            assert_eq!(MeetingLink::parse(link), Ok(expected), "{link}");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(MeetingLink::parse("zoom.us/j/123").is_err());
+        assert!(MeetingLink::parse("").is_err());
+    }
+}
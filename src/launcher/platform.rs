@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+use url::Url;
+
+use super::MeetingLink;
+
+/// Conferencing service a link belongs to, detected from its host.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Zoom,
+    #[strum(serialize = "Google Meet")]
+    Meet,
+    #[strum(serialize = "Microsoft Teams")]
+    Teams,
+    Jitsi,
+    Webex,
+    Other,
+}
+
+impl Platform {
+    pub fn detect(url: &Url) -> Platform {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let is_domain = |domain: &str| host == domain || host.ends_with(&format!(".{domain}"));
+        if is_domain("zoom.us") || is_domain("zoomgov.com") {
+            Platform::Zoom
+        } else if host == "meet.google.com" {
+            Platform::Meet
+        } else if host == "teams.microsoft.com" || host == "teams.live.com" {
+            Platform::Teams
+        } else if host == "meet.jit.si" || host.starts_with("jitsi.") {
+            Platform::Jitsi
+        } else if is_domain("webex.com") {
+            Platform::Webex
+        } else {
+            Platform::Other
+        }
+    }
+
+    /// Rewrites `url` to the URL scheme of the platform's desktop app, if it has one, so the
+    /// link opens the app directly instead of a browser page asking to open it.
+    pub fn native_url(&self, url: &Url, password: Option<&str>) -> Option<String> {
+        let host = url.host_str()?;
+        match self {
+            Platform::Zoom => {
+                let meeting = MeetingLink::from_url(url);
+                let meeting_id = meeting.meeting_id?;
+                let password = meeting.passcode.or(password.map(String::from));
+                let mut native = format!("zoommtg://{host}/join?action=join&confno={meeting_id}");
+                if let Some(password) = password {
+                    native.push_str("&pwd=");
+                    native.extend(url::form_urlencoded::byte_serialize(password.as_bytes()));
+                }
+                Some(native)
+            }
+            Platform::Teams => Some(format!("msteams:{}", &url[url::Position::BeforePath..])),
+            Platform::Jitsi => Some(format!(
+                "jitsi-meet://{host}{}",
+                &url[url::Position::BeforePath..]
+            )),
+            Platform::Meet | Platform::Webex | Platform::Other => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn url(link: &str) -> Url {
+        Url::parse(link).unwrap()
+    }
+
+    #[test]
+    fn test_detect() {
+        for (link, platform) in [
+            ("https://us02web.zoom.us/j/123", Platform::Zoom),
+            ("https://zoom.us/j/123", Platform::Zoom),
+            ("https://meet.google.com/abc-defg-hij", Platform::Meet),
+            (
+                "https://teams.microsoft.com/l/meetup-join/x",
+                Platform::Teams,
+            ),
+            ("https://meet.jit.si/Standup", Platform::Jitsi),
+            ("https://company.webex.com/meet/someone", Platform::Webex),
+            ("https://notzoom.us/j/123", Platform::Other),
+        ] {
+            assert_eq!(Platform::detect(&url(link)), platform, "{link}");
+        }
+    }
+
+    #[test]
+    fn test_native_url() {
+        let zoom = url("https://us02web.zoom.us/j/85412345678?pwd=abc123");
+        assert_eq!(
+            Platform::Zoom.native_url(&zoom, Some("ignored")).as_deref(),
+            Some("zoommtg://us02web.zoom.us/join?action=join&confno=85412345678&pwd=abc123")
+        );
+        let zoom = url("https://zoom.us/j/85412345678");
+        assert_eq!(
+            Platform::Zoom.native_url(&zoom, Some("a b")).as_deref(),
+            Some("zoommtg://zoom.us/join?action=join&confno=85412345678&pwd=a+b")
+        );
+        let teams = url("https://teams.microsoft.com/l/meetup-join/19%3ameeting?context=x");
+        assert_eq!(
+            Platform::Teams.native_url(&teams, None).as_deref(),
+            Some("msteams:/l/meetup-join/19%3ameeting?context=x")
+        );
+        let meet = url("https://meet.google.com/abc-defg-hij");
+        assert_eq!(Platform::Meet.native_url(&meet, None), None);
+    }
+}
@@ -1,17 +1,27 @@
 use clap::Parser;
-use cli::Cli;
-use color_eyre::Result;
+use cli::{Cli, Command, ConfigCommand};
+use color_eyre::{eyre::eyre, Result};
+use persistence::settings::SettingsLoader;
+use std::path::{Path, PathBuf};
 
 use crate::app::App;
+use crate::daemon::Daemon;
+use crate::instance::{InstanceKind, Message};
+use crate::persistence::history::JsonHistoryLoader;
 use crate::persistence::schedule::JsonScheduleLoader;
 use crate::persistence::settings::JsonSettingsLoader;
 
 mod action;
 mod app;
+mod autostart;
 mod cli;
+mod clipboard;
 mod config;
+mod daemon;
 mod entities;
 mod errors;
+mod instance;
+mod launcher;
 mod logging;
 mod persistence;
 mod theme;
@@ -19,20 +29,81 @@ mod tui;
 mod ui;
 mod utils;
 
+/// Where the schedule, the secret stores holding its passwords and the join history are kept.
+const SCHEDULE_DIR: &str = "./schedule";
+const SETTINGS_DIR: &str = "./cfg";
+/// Name of the schedule file in `SCHEDULE_DIR`.
+const SCHEDULE_NAME: &str = "schedule";
+
 #[tokio::main]
 async fn main() -> Result<()> {
     errors::init()?;
     logging::init()?;
 
     let args = Cli::parse();
-    let settings_loader = JsonSettingsLoader::new("./cfg");
-    let schedule_loader = JsonScheduleLoader::new("./schedule");
+    match args.command {
+        Some(Command::Config {
+            command: ConfigCommand::Check { path },
+        }) => return config::run_check(path),
+        Some(Command::Config {
+            command:
+                ConfigCommand::Dump {
+                    format,
+                    write,
+                    force,
+                },
+        }) => return config::run_dump(format, write, force),
+        Some(Command::Instance { message }) => return instance::run_send(message).await,
+        Some(Command::Schedule(command)) => {
+            return cli::run_schedule_command(
+                command,
+                Path::new(SCHEDULE_DIR),
+                &JsonSettingsLoader::new(SETTINGS_DIR).load(),
+                &mut JsonScheduleLoader::new(SCHEDULE_DIR),
+                &mut JsonHistoryLoader::new(SCHEDULE_DIR),
+            )
+            .await
+        }
+        Some(Command::Daemon) | None => {}
+    }
+    let is_daemon = matches!(args.command, Some(Command::Daemon));
+
+    // at most one daemon runs, and a user interface started while another one owns autostart
+    // brings that one up instead. Interfaces running next to a daemon stay in sync with each
+    // other by watching the schedule file.
+    match instance::find_running().await {
+        Some(status) if status.kind == InstanceKind::Daemon && is_daemon => {
+            return Err(eyre!("A daemon is already running (pid {})", status.pid));
+        }
+        Some(status) if status.kind == InstanceKind::Interface && !is_daemon => {
+            instance::send(Message::Show).await?;
+            println!("Already running (pid {})", status.pid);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let settings_loader = JsonSettingsLoader::new(SETTINGS_DIR);
+    let schedule_loader = JsonScheduleLoader::new(SCHEDULE_DIR);
+
+    if is_daemon {
+        let mut daemon = Daemon::new(
+            PathBuf::from(SCHEDULE_DIR),
+            Box::new(schedule_loader),
+            Box::new(settings_loader),
+            Box::new(JsonHistoryLoader::new(SCHEDULE_DIR)),
+        )?;
+        return daemon.run().await;
+    }
 
     let mut app = App::new(
         args.tick_rate,
         args.frame_rate,
+        args.mouse,
+        PathBuf::from(SCHEDULE_DIR),
         Box::new(schedule_loader),
         Box::new(settings_loader),
+        Box::new(JsonHistoryLoader::new(SCHEDULE_DIR)),
     )?;
 
     app.run().await?;
@@ -1,2 +1,4 @@
+pub mod history;
 pub mod schedule;
-pub mod settings;
\ No newline at end of file
+pub mod secrets;
+pub mod settings;
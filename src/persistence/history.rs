@@ -0,0 +1,95 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use color_eyre::Result;
+use tracing::error;
+
+use crate::entities::JoinRecord;
+
+pub trait HistoryLoader {
+    fn append(&mut self, record: &JoinRecord) -> Result<()>;
+    fn load(&mut self) -> Result<Vec<JoinRecord>>;
+}
+
+/// Keeps the join history as JSON lines, so recording a join only appends to the file.
+pub struct JsonHistoryLoader {
+    base_path: String,
+}
+
+impl JsonHistoryLoader {
+    pub fn new(base_path: &str) -> Self {
+        Self {
+            base_path: base_path.to_string(),
+        }
+    }
+}
+
+impl JsonHistoryLoader {
+    fn path(&self) -> PathBuf {
+        PathBuf::from(&self.base_path).join("history.jsonl")
+    }
+}
+
+impl HistoryLoader for JsonHistoryLoader {
+    fn append(&mut self, record: &JoinRecord) -> Result<()> {
+        fs::create_dir_all(&self.base_path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// Loads the history, oldest first. Lines that cannot be parsed are logged and skipped.
+    fn load(&mut self) -> Result<Vec<JoinRecord>> {
+        let contents = match fs::read_to_string(self.path()) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .inspect_err(|err| error!("Skipping join history entry: {err}"))
+                    .ok()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::entities::Conference;
+
+    #[test]
+    fn test_append_and_load() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut loader = JsonHistoryLoader::new(dir.path().to_str().unwrap());
+        assert_eq!(loader.load()?, Vec::new());
+
+        let conference = Conference {
+            title: "Standup".into(),
+            link: "https://meet.example.com/standup".into(),
+            ..Conference::default()
+        };
+        let records = [JoinRecord::new(&conference), JoinRecord::new(&conference)];
+        for record in &records {
+            loader.append(record)?;
+        }
+        fs::write(
+            loader.path(),
+            fs::read_to_string(loader.path())? + "not json\n",
+        )?;
+        let loaded = loader.load()?;
+
+        assert_eq!(loaded, records);
+        Ok(())
+    }
+}
@@ -1,32 +1,430 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+
 use crate::entities::Schedule;
+use crate::persistence::secrets::{new_secret_id, SecretStore};
+
+mod watcher;
+
+pub use watcher::{ScheduleWatcher, RELOAD_DELAY};
 
 pub trait ScheduleLoader {
-    fn save(&mut self, schedule: Schedule);
-    fn load(&mut self, name: &str) -> Schedule;
-    fn delete(&mut self, name: &str);
+    fn save(&mut self, schedule: &mut Schedule, secrets: &mut dyn SecretStore) -> Result<()>;
+    fn load(&mut self, name: &str, secrets: &mut dyn SecretStore) -> Result<Schedule>;
+
+    /// Reads the passwords the schedule refers to from `secrets`, if it is unlocked. Passwords
+    /// still kept in the schedule itself are moved into the store by saving the schedule.
+    fn resolve_passwords(
+        &mut self,
+        schedule: &mut Schedule,
+        secrets: &mut dyn SecretStore,
+    ) -> Result<()> {
+        if secrets.is_locked() {
+            return Ok(());
+        }
+        let mut has_plaintext_passwords = false;
+        for conference in schedule.conferences_mut() {
+            match &conference.password_id {
+                Some(id) => {
+                    // keep what is in memory if the store does not know the id, it may be a
+                    // new store the passwords are being moved to
+                    if let Some(password) = secrets.get(id)? {
+                        conference.password = Some(password);
+                    }
+                }
+                None => has_plaintext_passwords |= conference.password.is_some(),
+            }
+        }
+        if has_plaintext_passwords {
+            self.save(schedule, secrets)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct JsonScheduleLoader {
     base_path: String,
+    /// The schedule as it was last read or written here, to tell the changes made to it here
+    /// from those saved by other processes in the meantime.
+    known: Option<Schedule>,
 }
 
 impl JsonScheduleLoader {
     pub fn new(base_path: &str) -> Self {
         Self {
             base_path: base_path.to_string(),
+            known: None,
+        }
+    }
+}
+
+impl JsonScheduleLoader {
+    fn path(&self, name: &str) -> PathBuf {
+        PathBuf::from(&self.base_path).join(format!("{name}.json"))
+    }
+
+    /// Writes the schedule back with only its ids added, which does not need the secret store.
+    fn save_ids(&self, schedule: &Schedule) -> Result<()> {
+        fs::create_dir_all(&self.base_path)?;
+        let _lock = self.lock(&schedule.name)?;
+        write_atomically(
+            &self.path(&schedule.name),
+            &serde_json::to_string_pretty(schedule)?,
+        )
+    }
+
+    /// Waits for the lock of the schedule called `name`, which is held while it is saved so
+    /// that processes saving at the same time do not undo each other's changes. It is released
+    /// when the returned file is dropped.
+    fn lock(&self, name: &str) -> Result<File> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(PathBuf::from(&self.base_path).join(format!("{name}.lock")))?;
+        // SAFETY: the descriptor belongs to `file`, which is open for the whole call
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
         }
+        Ok(file)
+    }
+
+    /// Merges the changes other processes saved since the schedule was last read or written
+    /// here into `schedule`, so that saving it does not undo them.
+    fn merge_saved_changes(
+        &self,
+        schedule: &mut Schedule,
+        secrets: &mut dyn SecretStore,
+    ) -> Result<()> {
+        let Some(known) = self
+            .known
+            .as_ref()
+            .filter(|known| known.name == schedule.name)
+        else {
+            return Ok(());
+        };
+        let mut saved: Schedule = match fs::read_to_string(self.path(&schedule.name)) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        saved.name = schedule.name.clone();
+        saved.assign_missing_ids();
+        // compared with what is in memory, which has its passwords read from the store
+        for conference in saved.conferences_mut() {
+            let Some(id) = &conference.password_id else {
+                continue;
+            };
+            let stored = match secrets.is_locked() {
+                true => None,
+                false => secrets.get(id)?,
+            };
+            // keep what is in memory if the store does not know the id, it may be a new store
+            // the passwords are being moved to
+            conference.password = stored.or_else(|| {
+                known
+                    .get_conference(conference.id)
+                    .filter(|known| known.password_id.as_ref() == Some(id))
+                    .and_then(|known| known.password.clone())
+            });
+        }
+        if saved != *known {
+            schedule.merge(known, saved);
+        }
+        Ok(())
     }
 }
 
 impl ScheduleLoader for JsonScheduleLoader {
-    fn save(&mut self, schedule: Schedule) {
-        todo!()
+    /// Saves the schedule with its passwords replaced by ids in `secrets`. Passwords are
+    /// written to the store, and removed from it when they were cleared, which requires it to
+    /// be unlocked.
+    /// Changes saved by other processes since the schedule was read are kept, see
+    /// [`Schedule::merge`].
+    fn save(&mut self, schedule: &mut Schedule, secrets: &mut dyn SecretStore) -> Result<()> {
+        fs::create_dir_all(&self.base_path)?;
+        let _lock = self.lock(&schedule.name)?;
+        self.merge_saved_changes(schedule, secrets)?;
+
+        // cleared passwords are removed from the store only once the schedule no longer refers
+        // to them
+        let mut cleared = Vec::new();
+        for conference in schedule.conferences_mut() {
+            match (&conference.password, &conference.password_id) {
+                (Some(password), id) => {
+                    let id = id.clone().unwrap_or_else(new_secret_id);
+                    if secrets.get(&id)?.as_ref() != Some(password) {
+                        secrets.set(&id, password)?;
+                    }
+                    conference.password_id = Some(id);
+                }
+                // while the store is locked the password is just not loaded
+                (None, Some(id)) if !secrets.is_locked() => cleared.push(id.clone()),
+                (None, _) => {}
+            }
+        }
+
+        let mut stored = schedule.clone();
+        for conference in stored.conferences_mut() {
+            conference.password = None;
+            if conference
+                .password_id
+                .as_ref()
+                .is_some_and(|id| cleared.contains(id))
+            {
+                conference.password_id = None;
+            }
+        }
+        write_atomically(
+            &self.path(&schedule.name),
+            &serde_json::to_string_pretty(&stored)?,
+        )?;
+
+        for id in &cleared {
+            secrets.remove(id)?;
+        }
+        for (conference, written) in schedule.conferences_mut().zip(stored.conferences()) {
+            conference.password_id = written.password_id.clone();
+        }
+        self.known = Some(schedule.clone());
+        Ok(())
     }
 
-    fn load(&mut self, name: &str) -> Schedule {
-        todo!()
+    /// Loads the schedule called `name`, or an empty one if it has not been saved yet. The
+    /// name stored in the file is replaced by `name`, so that saving writes back to the file
+    /// the schedule was read from.
+    /// Conferences without an id get one, which is saved right away so it stays the same.
+    /// This only writes to schedules saved before conferences had ids, and only once.
+    fn load(&mut self, name: &str, secrets: &mut dyn SecretStore) -> Result<Schedule> {
+        let mut schedule: Schedule = match fs::read_to_string(self.path(name)) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                let schedule = Schedule::new(name.into());
+                self.known = Some(schedule.clone());
+                return Ok(schedule);
+            }
+            Err(err) => return Err(err.into()),
+        };
+        schedule.name = name.into();
+        if schedule.assign_missing_ids() {
+            self.save_ids(&schedule)?;
+        }
+        self.resolve_passwords(&mut schedule, secrets)?;
+        self.known = Some(schedule.clone());
+        Ok(schedule)
     }
-    fn delete(&mut self, name: &str) {
-        todo!()
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`, so that
+/// the file is never left half written and readers see either the old or the new contents.
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::entities::Conference;
+    use crate::persistence::secrets::{PlaintextSecretStore, VaultSecretStore};
+
+    fn conference(title: &str, password: Option<&str>) -> Conference {
+        Conference {
+            title: title.into(),
+            password: password.map(String::from),
+            ..Conference::default()
+        }
+    }
+
+    #[test]
+    fn test_passwords_are_kept_out_of_the_schedule_file() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut vault = VaultSecretStore::new(dir.join("vault.json"));
+        let mut schedule = Schedule::new("test".into());
+        schedule.add_conference(0, conference("Standup", Some("hunter2")));
+
+        assert!(loader.save(&mut schedule, &mut vault).is_err());
+        vault.unlock("passphrase")?;
+        loader.save(&mut schedule, &mut vault)?;
+        assert!(!fs::read_to_string(loader.path("test"))?.contains("hunter2"));
+
+        let mut vault = VaultSecretStore::new(dir.join("vault.json"));
+        let locked = loader.load("test", &mut vault)?;
+        assert_eq!(locked.get_day(0)[0].password, None);
+        assert!(locked.get_day(0)[0].password_id.is_some());
+
+        vault.unlock("passphrase")?;
+        let unlocked = loader.load("test", &mut vault)?;
+        assert_eq!(unlocked.get_day(0)[0].password.as_deref(), Some("hunter2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plaintext_passwords_are_migrated() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut schedule = Schedule::new("test".into());
+        schedule.add_conference(2, conference("Lecture", Some("s3cret")));
+        schedule.add_conference(2, conference("Seminar", None));
+        fs::write(loader.path("test"), serde_json::to_string(&schedule)?)?;
+
+        let mut secrets = PlaintextSecretStore::new(dir.join("secrets.json"));
+        let loaded = loader.load("test", &mut secrets)?;
+        let contents = fs::read_to_string(loader.path("test"))?;
+
+        let lecture = &loaded.get_day(2)[0];
+        assert_eq!(lecture.password.as_deref(), Some("s3cret"));
+        assert!(lecture.password_id.is_some());
+        assert_eq!(loaded.get_day(2)[1].password_id, None);
+        assert!(!contents.contains("s3cret"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cleared_password_is_kept_until_saved() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut secrets = PlaintextSecretStore::new(dir.join("secrets.json"));
+        let mut schedule = Schedule::new("test".into());
+        schedule.add_conference(0, conference("Standup", Some("hunter2")));
+        loader.save(&mut schedule, &mut secrets)?;
+        let id = schedule.get_day(0)[0].password_id.clone().unwrap();
+
+        let mut cleared = schedule.get_day(0)[0].clone();
+        cleared.password = None;
+        schedule.update_conference(cleared).unwrap();
+        // a directory in place of the file makes writing it fail
+        fs::remove_file(loader.path("test"))?;
+        fs::create_dir(loader.path("test"))?;
+        let failed = loader.save(&mut schedule, &mut secrets).is_err();
+        let kept = secrets.get(&id)?;
+        let still_referred = schedule.get_day(0)[0].password_id.is_some();
+
+        fs::remove_dir(loader.path("test"))?;
+        loader.save(&mut schedule, &mut secrets)?;
+        let removed = secrets.get(&id)?.is_none();
+
+        assert!(failed);
+        assert_eq!(kept.as_deref(), Some("hunter2"));
+        assert!(still_referred);
+        assert!(removed);
+        assert_eq!(schedule.get_day(0)[0].password_id, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_ids_are_assigned_once() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut schedule = Schedule::new("test".into());
+        schedule.add_conference(1, conference("Lecture", None));
+        let mut value = serde_json::to_value(&schedule)?;
+        value["schedule"][1][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("id");
+        // written by hand under another name
+        value["name"] = "renamed".into();
+        fs::write(loader.path("test"), value.to_string())?;
+
+        let mut secrets = PlaintextSecretStore::new(dir.join("secrets.json"));
+        let first = loader.load("test", &mut secrets)?;
+        let second = loader.load("test", &mut secrets)?;
+        let renamed = loader.path("renamed").exists();
+
+        assert!(!renamed);
+
+        let id = first.get_day(1)[0].id;
+        assert!(!id.is_nil());
+        assert_eq!(second.get_day(1)[0].id, id);
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes_saved_elsewhere_are_kept() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut secrets = PlaintextSecretStore::new(dir.join("secrets.json"));
+        let mut interface = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut schedule = interface.load("test", &mut secrets)?;
+        schedule.add_conference(0, conference("Standup", Some("hunter2")));
+        interface.save(&mut schedule, &mut secrets)?;
+
+        // the command line adds one before the interface reloaded the schedule
+        let mut command_line = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut changed = command_line.load("test", &mut secrets)?;
+        changed.add_conference(1, conference("Lecture", None));
+        command_line.save(&mut changed, &mut secrets)?;
+
+        schedule.add_conference(2, conference("Seminar", None));
+        interface.save(&mut schedule, &mut secrets)?;
+        let saved = command_line.load("test", &mut secrets)?;
+
+        for loaded in [&schedule, &saved] {
+            assert_eq!(loaded.get_day(0)[0].password.as_deref(), Some("hunter2"));
+            assert_eq!(loaded.get_day(1)[0].title, "Lecture");
+            assert_eq!(loaded.get_day(2)[0].title, "Seminar");
+        }
+        assert!(!dir.join("test.json.tmp").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_passwords_move_to_a_new_store() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut plaintext = PlaintextSecretStore::new(dir.join("secrets.json"));
+        let mut schedule = loader.load("test", &mut plaintext)?;
+        schedule.add_conference(0, conference("Standup", Some("hunter2")));
+        loader.save(&mut schedule, &mut plaintext)?;
+
+        let mut vault = VaultSecretStore::new(dir.join("vault.json"));
+        vault.unlock("passphrase")?;
+        loader.save(&mut schedule, &mut vault)?;
+        let loaded = loader.load("test", &mut vault)?;
+
+        assert_eq!(loaded.get_day(0)[0].password.as_deref(), Some("hunter2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_schedule_is_saved_to_the_file_it_was_loaded_from() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path();
+        let mut loader = JsonScheduleLoader::new(dir.to_str().unwrap());
+        let mut secrets = PlaintextSecretStore::new(dir.join("secrets.json"));
+        // written by hand under another name
+        let mut schedule = Schedule::new("renamed".into());
+        schedule.add_conference(3, conference("Lecture", None));
+        fs::write(loader.path("test"), serde_json::to_string(&schedule)?)?;
+
+        let mut loaded = loader.load("test", &mut secrets)?;
+        loaded.add_conference(3, conference("Seminar", None));
+        loader.save(&mut loaded, &mut secrets)?;
+        let mut reloaded = loader.load("test", &mut secrets)?;
+        reloaded.add_conference(4, conference("Exam", None));
+        loader.save(&mut reloaded, &mut secrets)?;
+        let saved = loader.load("test", &mut secrets)?;
+
+        assert!(!loader.path("renamed").exists());
+        assert_eq!(saved.name, "test");
+        assert_eq!(saved.get_day(3).len(), 2);
+        assert_eq!(saved.get_day(4)[0].title, "Exam");
+        Ok(())
     }
 }
@@ -0,0 +1,48 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::Duration;
+
+use color_eyre::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+/// How long the schedule file has to stay untouched before it is reloaded.
+pub const RELOAD_DELAY: Duration = Duration::from_millis(300);
+
+/// Watches the schedule directory and sends `message` whenever the schedule file changes.
+pub struct ScheduleWatcher {
+    // kept alive for as long as the directory should be watched
+    _watcher: RecommendedWatcher,
+}
+
+impl ScheduleWatcher {
+    pub fn new<T: Clone + Send + 'static>(
+        schedule_dir: &Path,
+        file_name: &str,
+        change_tx: UnboundedSender<T>,
+        message: T,
+    ) -> Result<Self> {
+        let file_name = OsString::from(file_name);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) if is_change_of(&event, &file_name) => {
+                    let _ = change_tx.send(message.clone());
+                }
+                Ok(_) => {}
+                Err(err) => error!("Schedule watcher error: {err}"),
+            })?;
+        watcher.watch(schedule_dir, RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn is_change_of(event: &Event, file_name: &OsString) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|path| path.file_name() == Some(file_name.as_os_str()))
+}
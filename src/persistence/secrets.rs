@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+mod plaintext;
+mod vault;
+
+pub use plaintext::PlaintextSecretStore;
+pub use vault::VaultSecretStore;
+
+/// A secret store shared between the app, the schedule loader and the unlock prompt. The
+/// store inside can be swapped when the user switches backends.
+pub type SharedSecretStore = Rc<RefCell<Box<dyn SecretStore>>>;
+
+/// Keeps secrets such as conference passwords out of the schedule file, which only refers to
+/// them by id.
+pub trait SecretStore {
+    /// Whether the store has to be unlocked before secrets can be read or written.
+    fn is_locked(&self) -> bool;
+    /// Whether the store has been created yet. A store that does not exist is created by
+    /// unlocking it, with the passphrase given then.
+    fn exists(&self) -> bool;
+    fn unlock(&mut self, passphrase: &str) -> Result<()>;
+    /// Forgets the decrypted secrets until the store is unlocked again.
+    fn lock(&mut self);
+    fn get(&self, id: &str) -> Result<Option<String>>;
+    fn set(&mut self, id: &str, secret: &str) -> Result<()>;
+    fn remove(&mut self, id: &str) -> Result<()>;
+    /// Deletes the store along with every secret in it.
+    fn delete(&mut self) -> Result<()>;
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum SecretBackend {
+    #[default]
+    #[strum(serialize = "Encrypted vault")]
+    Vault,
+    #[strum(serialize = "Plain file")]
+    Plaintext,
+}
+
+impl SecretBackend {
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::iter().find(|backend| backend.to_string() == label)
+    }
+
+    pub fn open(&self, dir: &Path) -> Box<dyn SecretStore> {
+        match self {
+            SecretBackend::Vault => Box::new(VaultSecretStore::new(dir.join("vault.json"))),
+            SecretBackend::Plaintext => {
+                Box::new(PlaintextSecretStore::new(dir.join("secrets.json")))
+            }
+        }
+    }
+
+    /// Returns the backend whose store in `dir` holds the secrets. That is `self` unless its
+    /// store has not been created yet while the store of another backend has, in which case
+    /// the secrets still have to be moved over.
+    pub fn find_existing(&self, dir: &Path) -> SecretBackend {
+        if self.open(dir).exists() {
+            return *self;
+        }
+        Self::iter()
+            .find(|backend| backend.open(dir).exists())
+            .unwrap_or(*self)
+    }
+}
+
+/// Returns a new random id to store a secret under.
+pub fn new_secret_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
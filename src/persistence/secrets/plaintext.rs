@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use color_eyre::Result;
+
+use super::SecretStore;
+
+/// Stores secrets unencrypted in a JSON file, for users who would rather not enter a
+/// passphrase. It never needs to be unlocked.
+pub struct PlaintextSecretStore {
+    path: PathBuf,
+}
+
+impl PlaintextSecretStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read(&self) -> Result<HashMap<String, String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write(&self, secrets: &HashMap<String, String>) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(secrets)?)?;
+        Ok(())
+    }
+}
+
+impl SecretStore for PlaintextSecretStore {
+    fn is_locked(&self) -> bool {
+        false
+    }
+
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn unlock(&mut self, _passphrase: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn lock(&mut self) {}
+
+    fn get(&self, id: &str) -> Result<Option<String>> {
+        Ok(self.read()?.remove(id))
+    }
+
+    fn set(&mut self, id: &str, secret: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+        secrets.insert(id.into(), secret.into());
+        self.write(&secrets)
+    }
+
+    fn remove(&mut self, id: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+        if secrets.remove(id).is_some() {
+            self.write(&secrets)?;
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
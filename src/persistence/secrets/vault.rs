@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use super::SecretStore;
+
+const VERSION: u32 = 1;
+const SALT_LENGTH: usize = 16;
+
+/// The vault as written to disk. The secrets are encrypted together, with a key derived from
+/// the passphrase by Argon2.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct Unlocked {
+    key: Key,
+    salt: [u8; SALT_LENGTH],
+    secrets: HashMap<String, String>,
+}
+
+/// Stores secrets in a file encrypted with a passphrase, which has to be entered once per
+/// session before the secrets can be used.
+pub struct VaultSecretStore {
+    path: PathBuf,
+    unlocked: Option<Unlocked>,
+}
+
+impl VaultSecretStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            unlocked: None,
+        }
+    }
+
+    fn unlocked(&self) -> Result<&Unlocked> {
+        self.unlocked
+            .as_ref()
+            .ok_or_else(|| eyre!("The password vault is locked"))
+    }
+
+    fn unlocked_mut(&mut self) -> Result<&mut Unlocked> {
+        self.unlocked
+            .as_mut()
+            .ok_or_else(|| eyre!("The password vault is locked"))
+    }
+
+    fn write(&self) -> Result<()> {
+        let unlocked = self.unlocked()?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(&unlocked.secrets)?;
+        let ciphertext = ChaCha20Poly1305::new(&unlocked.key)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| eyre!("Failed to encrypt the password vault"))?;
+        let file = VaultFile {
+            version: VERSION,
+            salt: BASE64.encode(unlocked.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| eyre!("Failed to derive the vault key: {err}"))?;
+    Ok(key)
+}
+
+impl SecretStore for VaultSecretStore {
+    fn is_locked(&self) -> bool {
+        self.unlocked.is_none()
+    }
+
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        if self.unlocked.is_some() {
+            return Ok(());
+        }
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                let mut salt = [0u8; SALT_LENGTH];
+                OsRng.fill_bytes(&mut salt);
+                self.unlocked = Some(Unlocked {
+                    key: derive_key(passphrase, &salt)?,
+                    salt,
+                    secrets: HashMap::new(),
+                });
+                // written right away, so the next session asks for the same passphrase
+                return self.write();
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let file: VaultFile = serde_json::from_str(&contents)?;
+        if file.version != VERSION {
+            return Err(eyre!("Unsupported vault version {}", file.version));
+        }
+        let salt: [u8; SALT_LENGTH] = BASE64
+            .decode(file.salt)?
+            .try_into()
+            .map_err(|_| eyre!("Invalid vault salt"))?;
+        let nonce = BASE64.decode(file.nonce)?;
+        if nonce.len() != 12 {
+            return Err(eyre!("Invalid vault nonce"));
+        }
+        let key = derive_key(passphrase, &salt)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                BASE64.decode(file.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| eyre!("Wrong passphrase"))?;
+        self.unlocked = Some(Unlocked {
+            key,
+            salt,
+            secrets: serde_json::from_slice(&plaintext)?,
+        });
+        Ok(())
+    }
+
+    fn lock(&mut self) {
+        self.unlocked = None;
+    }
+
+    fn get(&self, id: &str) -> Result<Option<String>> {
+        Ok(self.unlocked()?.secrets.get(id).cloned())
+    }
+
+    fn set(&mut self, id: &str, secret: &str) -> Result<()> {
+        self.unlocked_mut()?
+            .secrets
+            .insert(id.into(), secret.into());
+        self.write()
+    }
+
+    fn remove(&mut self, id: &str) -> Result<()> {
+        if self.unlocked_mut()?.secrets.remove(id).is_some() {
+            self.write()?;
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self) -> Result<()> {
+        self.unlocked = None;
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_and_unlock() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("vault.json");
+        let mut vault = VaultSecretStore::new(path.clone());
+        assert!(vault.is_locked());
+        assert!(vault.set("id", "secret").is_err());
+
+        vault.unlock("correct horse")?;
+        vault.set("id", "secret")?;
+        assert_eq!(vault.get("id")?.as_deref(), Some("secret"));
+        assert!(!fs::read_to_string(&path)?.contains("secret"));
+
+        vault.lock();
+        assert!(vault.get("id").is_err());
+
+        let mut reopened = VaultSecretStore::new(path);
+        assert!(reopened.unlock("battery staple").is_err());
+        assert!(reopened.is_locked());
+        reopened.unlock("correct horse")?;
+        assert_eq!(reopened.get("id")?.as_deref(), Some("secret"));
+
+        reopened.delete()?;
+        assert!(!reopened.exists());
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use color_eyre::Result;
+use tracing::error;
+
 use crate::entities::Settings;
 
 pub trait SettingsLoader {
     fn load(&mut self) -> Settings;
-    fn save(&mut self, settings: Settings);
+    fn save(&mut self, settings: Settings) -> Result<()>;
 }
 
 pub struct JsonSettingsLoader {
@@ -17,11 +24,63 @@ impl JsonSettingsLoader {
     }
 }
 
+impl JsonSettingsLoader {
+    fn path(&self) -> PathBuf {
+        PathBuf::from(&self.base_path).join("settings.json")
+    }
+}
+
 impl SettingsLoader for JsonSettingsLoader {
+    /// Loads the saved settings, falling back to the defaults if there are none or they
+    /// cannot be read.
     fn load(&mut self) -> Settings {
-        todo!()
+        let path = self.path();
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Settings::default(),
+            Err(err) => {
+                error!("Failed to read {}: {err}", path.display());
+                return Settings::default();
+            }
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|err| {
+            error!("Failed to parse {}: {err}", path.display());
+            Settings::default()
+        })
+    }
+
+    fn save(&mut self, settings: Settings) -> Result<()> {
+        fs::create_dir_all(&self.base_path)?;
+        fs::write(self.path(), serde_json::to_string_pretty(&settings)?)?;
+        Ok(())
     }
-    fn save(&mut self, settings: Settings) {
-        todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::persistence::secrets::SecretBackend;
+    use crate::theme::ThemeName;
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut loader = JsonSettingsLoader::new(dir.path().to_str().unwrap());
+        assert_eq!(loader.load(), Settings::default());
+
+        let settings = Settings {
+            autostart: true,
+            early_join_minutes: 5,
+            theme: ThemeName::HighContrast,
+            mouse: true,
+            secret_store: SecretBackend::Plaintext,
+        };
+        loader.save(settings.clone())?;
+        let loaded = loader.load();
+
+        assert_eq!(loaded, settings);
+        Ok(())
     }
 }
@@ -1,13 +1,34 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
 use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
+use tracing::warn;
+
+use crate::action::Mode;
+use crate::config::Styles;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Theme {
+    pub text: Style,
     pub selected_text: Style,
     pub selected_field: Style,
     pub active_field: Style,
     pub input_field: Style,
+    pub list_item: Style,
+    pub status_bar: Style,
+    pub conflict: Style,
+    pub live_conference: Style,
+    pub notification_info: Style,
+    pub notification_warning: Style,
+    pub notification_error: Style,
 }
 
-pub const THEME: Theme = Theme {
+pub const DARK: Theme = Theme {
+    text: Style::new().fg(Color::White),
     selected_text: Style::new()
         .fg(Color::White)
         .bg(Color::DarkGray)
@@ -15,4 +36,212 @@ pub const THEME: Theme = Theme {
     input_field: Style::new().fg(Color::White),
     selected_field: Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
     active_field: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    list_item: Style::new().fg(Color::White),
+    status_bar: Style::new().fg(Color::Black).bg(Color::Gray),
+    conflict: Style::new().fg(Color::Red),
+    live_conference: Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+    notification_info: Style::new().fg(Color::Cyan),
+    notification_warning: Style::new().fg(Color::Yellow),
+    notification_error: Style::new().fg(Color::Red),
+};
+
+pub const LIGHT: Theme = Theme {
+    text: Style::new().fg(Color::Black),
+    selected_text: Style::new()
+        .fg(Color::Black)
+        .bg(Color::Gray)
+        .add_modifier(Modifier::BOLD),
+    input_field: Style::new().fg(Color::Black),
+    selected_field: Style::new().fg(Color::Black).add_modifier(Modifier::BOLD),
+    active_field: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+    list_item: Style::new().fg(Color::Black),
+    status_bar: Style::new().fg(Color::White).bg(Color::DarkGray),
+    conflict: Style::new().fg(Color::Red),
+    live_conference: Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+    notification_info: Style::new().fg(Color::Blue),
+    notification_warning: Style::new().fg(Color::Magenta),
+    notification_error: Style::new().fg(Color::Red),
+};
+
+pub const HIGH_CONTRAST: Theme = Theme {
+    text: Style::new().fg(Color::White).bg(Color::Black),
+    selected_text: Style::new()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD),
+    input_field: Style::new().fg(Color::White),
+    selected_field: Style::new()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD.union(Modifier::UNDERLINED)),
+    active_field: Style::new()
+        .fg(Color::LightCyan)
+        .add_modifier(Modifier::BOLD),
+    list_item: Style::new().fg(Color::White).bg(Color::Black),
+    status_bar: Style::new().fg(Color::Black).bg(Color::White),
+    conflict: Style::new()
+        .fg(Color::LightRed)
+        .add_modifier(Modifier::BOLD),
+    live_conference: Style::new()
+        .fg(Color::LightGreen)
+        .add_modifier(Modifier::BOLD),
+    notification_info: Style::new().fg(Color::LightCyan),
+    notification_warning: Style::new().fg(Color::LightYellow),
+    notification_error: Style::new().fg(Color::LightRed),
 };
+
+pub const NO_COLOR: Theme = Theme {
+    text: Style::new(),
+    selected_text: Style::new().add_modifier(Modifier::REVERSED),
+    input_field: Style::new(),
+    selected_field: Style::new().add_modifier(Modifier::BOLD),
+    active_field: Style::new().add_modifier(Modifier::BOLD.union(Modifier::UNDERLINED)),
+    list_item: Style::new(),
+    status_bar: Style::new().add_modifier(Modifier::REVERSED),
+    conflict: Style::new().add_modifier(Modifier::UNDERLINED),
+    live_conference: Style::new().add_modifier(Modifier::BOLD),
+    notification_info: Style::new(),
+    notification_warning: Style::new().add_modifier(Modifier::BOLD),
+    notification_error: Style::new().add_modifier(Modifier::BOLD.union(Modifier::REVERSED)),
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    #[strum(serialize = "High contrast")]
+    HighContrast,
+    #[strum(serialize = "No color")]
+    NoColor,
+}
+
+impl ThemeName {
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeName::Dark => DARK,
+            ThemeName::Light => LIGHT,
+            ThemeName::HighContrast => HIGH_CONTRAST,
+            ThemeName::NoColor => NO_COLOR,
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::iter().find(|name| name.to_string() == label)
+    }
+}
+
+impl Theme {
+    /// Replaces the style named `key`, returning `false` if there is no such style.
+    pub fn set(&mut self, key: &str, style: Style) -> bool {
+        let slot = match key {
+            "text" => &mut self.text,
+            "selected_text" => &mut self.selected_text,
+            "selected_field" => &mut self.selected_field,
+            "active_field" => &mut self.active_field,
+            "input_field" => &mut self.input_field,
+            "list_item" => &mut self.list_item,
+            "status_bar" => &mut self.status_bar,
+            "conflict" => &mut self.conflict,
+            "live_conference" => &mut self.live_conference,
+            "notification_info" => &mut self.notification_info,
+            "notification_warning" => &mut self.notification_warning,
+            "notification_error" => &mut self.notification_error,
+            _ => return false,
+        };
+        *slot = style;
+        true
+    }
+}
+
+struct ThemeState {
+    name: ThemeName,
+    overrides: HashMap<Mode, HashMap<String, Style>>,
+    mode: Mode,
+    resolved: Theme,
+}
+
+impl ThemeState {
+    fn resolve(&mut self) {
+        let no_color = env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+        let name = if no_color {
+            ThemeName::NoColor
+        } else {
+            self.name
+        };
+        let mut theme = name.theme();
+        for (key, style) in self.overrides.get(&self.mode).into_iter().flatten() {
+            let style = if no_color {
+                Style {
+                    fg: None,
+                    bg: None,
+                    ..*style
+                }
+            } else {
+                *style
+            };
+            if !theme.set(key, style) {
+                warn!("Unknown style `{key}` in {:?} styles", self.mode);
+            }
+        }
+        self.resolved = theme;
+    }
+}
+
+lazy_static! {
+    static ref STATE: RwLock<ThemeState> = RwLock::new(ThemeState {
+        name: ThemeName::default(),
+        overrides: HashMap::new(),
+        mode: Mode::default(),
+        resolved: DARK,
+    });
+}
+
+fn update_state(update: impl FnOnce(&mut ThemeState)) {
+    let mut state = STATE.write().expect("theme lock should not be poisoned");
+    update(&mut state);
+    state.resolve();
+}
+
+/// Returns the theme for the active mode, with the styles from the config applied.
+pub fn current() -> Theme {
+    STATE
+        .read()
+        .expect("theme lock should not be poisoned")
+        .resolved
+}
+
+pub fn set_theme(name: ThemeName) {
+    update_state(|state| state.name = name);
+}
+
+pub fn set_styles(styles: &Styles) {
+    update_state(|state| state.overrides = styles.0.clone());
+}
+
+pub fn set_mode(mode: Mode) {
+    update_state(|state| state.mode = mode);
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_theme_names_round_trip() {
+        for name in ThemeName::iter() {
+            assert_eq!(ThemeName::from_label(&name.to_string()), Some(name));
+        }
+        assert_eq!(ThemeName::from_label("Solarized"), None);
+    }
+
+    #[test]
+    fn test_set_style() {
+        let mut theme = DARK;
+        let style = Style::new().fg(Color::Magenta);
+        assert!(theme.set("status_bar", style));
+        assert_eq!(theme.status_bar, style);
+        assert!(!theme.set("unknown", style));
+    }
+}
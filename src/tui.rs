@@ -85,6 +85,19 @@ impl Tui {
         self
     }
 
+    /// Turns mouse capture on or off while the terminal is already set up.
+    pub fn set_mouse(&mut self, mouse: bool) -> Result<()> {
+        if mouse != self.mouse {
+            if mouse {
+                crossterm::execute!(stdout(), EnableMouseCapture)?;
+            } else {
+                crossterm::execute!(stdout(), DisableMouseCapture)?;
+            }
+            self.mouse = mouse;
+        }
+        Ok(())
+    }
+
     pub fn paste(mut self, paste: bool) -> Self {
         self.paste = paste;
         self
@@ -8,10 +8,13 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{action::Action, config::Config, tui::Event};
 
+mod command;
 mod components;
 mod input;
 mod pages;
 
+pub use command::{describe_action, global_commands, Command, KeyHint};
+pub use components::Notifications;
 pub use pages::home::Home;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
@@ -71,6 +74,7 @@ pub trait Component {
         let action = match event {
             Some(Event::Key(key_event)) => self.handle_key_event(key_event)?,
             Some(Event::Mouse(mouse_event)) => self.handle_mouse_event(mouse_event)?,
+            Some(Event::Paste(text)) => self.handle_paste(text)?,
             _ => None,
         };
         Ok(action)
@@ -101,6 +105,19 @@ pub trait Component {
         let _ = mouse; // to appease clippy
         Ok(None)
     }
+    /// Handle text pasted into the terminal and produce actions if necessary.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted text.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Action>>` - An action to be processed or none.
+    fn handle_paste(&mut self, text: String) -> Result<Option<Action>> {
+        let _ = text; // to appease clippy
+        Ok(None)
+    }
     /// Update the state of the component based on a received action. (REQUIRED)
     ///
     /// # Arguments
@@ -0,0 +1,62 @@
+use crate::action::{Action, Mode};
+use crossterm::event::KeyEvent;
+
+/// A user-facing command that can be dispatched from the command palette.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub description: &'static str,
+    pub action: Action,
+}
+
+impl Command {
+    pub fn new(description: &'static str, action: Action) -> Self {
+        Self {
+            description,
+            action,
+        }
+    }
+}
+
+/// Commands available regardless of the active page.
+pub fn global_commands() -> Vec<Command> {
+    vec![
+        Command::new("Quit", Action::Quit),
+        Command::new("Suspend", Action::Suspend),
+        Command::new("Clear screen", Action::ClearScreen),
+        Command::new("Show help", Action::Help),
+        Command::new(
+            "Show notification history",
+            Action::ToggleNotificationHistory,
+        ),
+        Command::new("Go to schedule", Action::ChangeMode(Mode::Schedule)),
+        Command::new("Go to settings", Action::ChangeMode(Mode::Settings)),
+        Command::new("Join the live conference", Action::JoinLive),
+        Command::new("Unlock password vault", Action::UnlockSecrets),
+        Command::new("Lock password vault", Action::LockSecrets),
+    ]
+}
+
+/// A key a page handles on its own, shown in the help overlay.
+#[derive(Debug, Clone)]
+pub struct KeyHint {
+    pub keys: Vec<KeyEvent>,
+    pub description: &'static str,
+}
+
+impl KeyHint {
+    pub fn new(keys: Vec<KeyEvent>, description: &'static str) -> Self {
+        Self { keys, description }
+    }
+}
+
+/// Returns a human readable description of `action`, preferring the description of a matching
+/// command from `commands`.
+pub fn describe_action(action: &Action, commands: &[Command]) -> String {
+    commands
+        .iter()
+        .find(|command| command.action == *action)
+        .map_or_else(
+            || format!("{action:?}"),
+            |command| command.description.into(),
+        )
+}
@@ -1,7 +1,15 @@
+mod command_palette;
 mod fps;
+mod help;
+mod notifications;
 mod selector;
 mod selector_2d;
+mod unlock_prompt;
 
+pub use command_palette::CommandPalette;
 pub use fps::FpsCounter;
+pub use help::HelpOverlay;
+pub use notifications::Notifications;
 pub use selector::Selector;
 pub use selector_2d::Selector2D;
+pub use unlock_prompt::UnlockPrompt;
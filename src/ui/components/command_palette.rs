@@ -0,0 +1,204 @@
+use crate::action::{Action, Mode};
+use crate::config::{key_sequence_to_string, Config};
+use crate::theme;
+use crate::ui::input::fields::{InputField, StrInputField};
+use crate::ui::{Command, Component};
+use crate::utils;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Stylize;
+use ratatui::widgets::{Block, Borders, Clear, Row, Table, TableState};
+use ratatui::Frame;
+use tokio::sync::mpsc::UnboundedSender;
+
+struct Entry {
+    description: &'static str,
+    keys: String,
+    action: Action,
+}
+
+pub struct CommandPalette {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    matcher: SkimMatcherV2,
+    query: StrInputField,
+    entries: Vec<Entry>,
+    filtered: Vec<usize>,
+    selected: usize,
+    previous_mode: Mode,
+    is_open: bool,
+}
+
+impl Default for CommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            command_tx: None,
+            config: Config::default(),
+            matcher: SkimMatcherV2::default().ignore_case(),
+            query: Self::new_query_field(),
+            entries: Vec::new(),
+            filtered: Vec::new(),
+            selected: 0,
+            previous_mode: Mode::default(),
+            is_open: false,
+        }
+    }
+
+    fn new_query_field() -> StrInputField {
+        let mut query = StrInputField::new(None, 50, None);
+        query.set_cursor_visibility(true);
+        query
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Opens the palette with `commands`, looking up their bindings in the keymap of `mode`.
+    pub fn open(&mut self, commands: Vec<Command>, mode: Mode) {
+        let keymap = self.config.keybindings.get(&mode);
+        self.entries = commands
+            .into_iter()
+            .map(|command| {
+                let mut keys: Vec<String> = keymap
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, action)| **action == command.action)
+                    .map(|(sequence, _)| key_sequence_to_string(sequence))
+                    .collect();
+                keys.sort();
+                Entry {
+                    description: command.description,
+                    keys: keys.join(" "),
+                    action: command.action,
+                }
+            })
+            .collect();
+        self.query = Self::new_query_field();
+        self.previous_mode = mode;
+        self.is_open = true;
+        self.refresh_filter();
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.is_open = false;
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ChangeMode(self.previous_mode))?;
+        }
+        Ok(())
+    }
+
+    fn dispatch_selected(&mut self) -> Result<()> {
+        let action = self
+            .filtered
+            .get(self.selected)
+            .map(|&index| self.entries[index].action.clone());
+        self.close()?;
+        if let (Some(action), Some(tx)) = (action, &self.command_tx) {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+
+    fn refresh_filter(&mut self) {
+        let query = self.query.get_value();
+        let mut scored: Vec<(usize, i64)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                if query.is_empty() {
+                    return Some((index, 0));
+                }
+                self.matcher
+                    .fuzzy_match(entry.description, &query)
+                    .map(|score| (index, score))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        self.filtered = scored.into_iter().map(|(index, _)| index).collect();
+        self.selected = 0;
+    }
+
+    fn select_prev(&mut self) {
+        if !self.filtered.is_empty() {
+            self.selected = match self.selected {
+                0 => self.filtered.len() - 1,
+                _ => self.selected - 1,
+            };
+        }
+    }
+
+    fn select_next(&mut self) {
+        if !self.filtered.is_empty() {
+            self.selected = (self.selected + 1) % self.filtered.len();
+        }
+    }
+}
+
+impl Component for CommandPalette {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Enter => self.dispatch_selected()?,
+            KeyCode::Up => self.select_prev(),
+            KeyCode::Down => self.select_next(),
+            _ => {
+                self.query.handle_key_event(key)?;
+                self.refresh_filter();
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste(&mut self, text: String) -> Result<Option<Action>> {
+        self.query.handle_paste(text)?;
+        self.refresh_filter();
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+        let area = utils::centered_rect(area, 60, 20);
+        frame.render_widget(Clear, area);
+        let block = Block::default().borders(Borders::ALL).title("Commands");
+        frame.render_widget(block.clone(), area);
+
+        let [query_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(block.inner(area));
+        self.query.draw(frame, query_area)?;
+
+        let rows = self.filtered.iter().map(|&index| {
+            let entry = &self.entries[index];
+            Row::new([entry.description.to_string(), entry.keys.clone()])
+        });
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(20)])
+            .row_highlight_style(theme::current().selected_text)
+            .column_spacing(1)
+            .header(Row::new(["Command", "Keys"]).dim());
+        let mut state = TableState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(table, list_area, &mut state);
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use crate::action::{Action, Mode};
+use crate::config::{key_sequence_to_string, Config};
+use crate::ui::{describe_action, Command, Component, KeyHint};
+use crate::utils;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::{Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Row, Table, TableState};
+use ratatui::Frame;
+
+struct HelpSection {
+    title: String,
+    rows: Vec<(String, String)>,
+}
+
+/// Overlay listing the keys of the active mode, generated from the keybinding configuration.
+pub struct HelpOverlay {
+    config: Config,
+    sections: Vec<HelpSection>,
+    scroll: usize,
+    previous_mode: Mode,
+    is_open: bool,
+}
+
+impl Default for HelpOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HelpOverlay {
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+            sections: Vec::new(),
+            scroll: 0,
+            previous_mode: Mode::default(),
+            is_open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Opens the overlay for `mode`, listing its keymap followed by the page's own keys.
+    pub fn open(&mut self, mode: Mode, commands: &[Command], page: &str, hints: Vec<KeyHint>) {
+        let mut keymap_rows: Vec<(String, String)> = self
+            .config
+            .keybindings
+            .get(&mode)
+            .into_iter()
+            .flatten()
+            .map(|(keys, action)| {
+                (
+                    key_sequence_to_string(keys),
+                    describe_action(action, commands),
+                )
+            })
+            .collect();
+        keymap_rows.sort();
+        let page_rows = hints
+            .into_iter()
+            .filter(|hint| !hint.keys.is_empty())
+            .map(|hint| {
+                let keys: Vec<String> = hint
+                    .keys
+                    .iter()
+                    .map(|key| key_sequence_to_string(&[*key]))
+                    .collect();
+                (keys.join(" "), hint.description.to_string())
+            })
+            .collect();
+        self.sections = vec![
+            HelpSection {
+                title: format!("{mode:?} keymap"),
+                rows: keymap_rows,
+            },
+            HelpSection {
+                title: page.to_string(),
+                rows: page_rows,
+            },
+        ];
+        self.sections.retain(|section| !section.rows.is_empty());
+        self.scroll = 0;
+        self.previous_mode = mode;
+        self.is_open = true;
+    }
+
+    /// Closes the overlay and returns the mode that was active before it was opened.
+    pub fn close(&mut self) -> Mode {
+        self.is_open = false;
+        self.previous_mode
+    }
+
+    fn row_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.rows.len() + 2)
+            .sum()
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max_scroll = self.row_count().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+impl Component for HelpOverlay {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => return Ok(Some(Action::Help)),
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-10),
+            KeyCode::PageDown => self.scroll_by(10),
+            KeyCode::Home => self.scroll = 0,
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+        let area = utils::centered_rect(area, 70, 24);
+        frame.render_widget(Clear, area);
+
+        let mut rows = Vec::new();
+        for section in &self.sections {
+            rows.push(
+                Row::new([section.title.clone()])
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            );
+            rows.extend(
+                section
+                    .rows
+                    .iter()
+                    .map(|(keys, description)| Row::new([keys.clone(), description.clone()])),
+            );
+            rows.push(Row::default());
+        }
+        let table = Table::new(rows, [Constraint::Length(24), Constraint::Fill(1)])
+            .column_spacing(2)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Help")
+                    .title_bottom("<esc> close, <up>/<down> scroll".dim()),
+            );
+        let mut state = TableState::default().with_offset(self.scroll);
+        frame.render_stateful_widget(table, area, &mut state);
+        Ok(())
+    }
+}
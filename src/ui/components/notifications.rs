@@ -0,0 +1,220 @@
+use crate::action::{Action, Mode, Notification, NotificationLevel};
+use crate::theme;
+use crate::ui::Component;
+use crate::utils;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+const TOAST_WIDTH: u16 = 42;
+const MAX_VISIBLE_TOASTS: usize = 5;
+const HISTORY_LENGTH: usize = 100;
+
+struct Entry {
+    notification: Notification,
+    created_at: Instant,
+}
+
+/// Stacked, auto-expiring toasts with a history of recent messages.
+pub struct Notifications {
+    toasts: VecDeque<Entry>,
+    history: VecDeque<Entry>,
+    history_selected: usize,
+    previous_mode: Mode,
+    mode: Mode,
+    is_history_open: bool,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self {
+            toasts: VecDeque::new(),
+            history: VecDeque::new(),
+            history_selected: 0,
+            previous_mode: Mode::default(),
+            mode: Mode::default(),
+            is_history_open: false,
+        }
+    }
+
+    fn push(&mut self, notification: Notification) {
+        let created_at = Instant::now();
+        self.history.push_front(Entry {
+            notification: notification.clone(),
+            created_at,
+        });
+        self.history.truncate(HISTORY_LENGTH);
+        self.toasts.push_front(Entry {
+            notification,
+            created_at,
+        });
+    }
+
+    fn expire_toasts(&mut self) {
+        self.toasts
+            .retain(|toast| toast.created_at.elapsed() < lifetime(toast.notification.level));
+    }
+
+    fn toggle_history(&mut self) -> Option<Action> {
+        self.is_history_open = !self.is_history_open;
+        if self.is_history_open {
+            self.previous_mode = self.mode;
+            self.history_selected = 0;
+            Some(Action::ChangeMode(Mode::Notifications))
+        } else {
+            Some(Action::ChangeMode(self.previous_mode))
+        }
+    }
+
+    fn render_toasts(&self, frame: &mut Frame, area: Rect) {
+        let mut bottom = area.bottom();
+        for toast in self.toasts.iter().take(MAX_VISIBLE_TOASTS) {
+            let width = TOAST_WIDTH.min(area.width);
+            let text_width = width.saturating_sub(2).max(1) as usize;
+            let lines = line_count(&toast.notification.message, text_width);
+            let height = (lines.clamp(1, 4) as u16 + 2).min(bottom - area.y);
+            if height < 3 {
+                break;
+            }
+            let toast_area = Rect::new(area.right() - width, bottom - height, width, height);
+            let style = level_style(toast.notification.level);
+            let paragraph = Paragraph::new(toast.notification.message.as_str())
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(style)
+                        .title(Span::styled(toast.notification.level.to_string(), style)),
+                );
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(paragraph, toast_area);
+            bottom -= height;
+        }
+    }
+
+    fn render_history(&self, frame: &mut Frame, area: Rect) {
+        let area = utils::centered_rect(area, 80, 20);
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Notifications")
+            .title_bottom("<esc> close".dim());
+        let items = self.history.iter().map(|entry| {
+            let style = level_style(entry.notification.level);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<8}", entry.notification.level.to_string()),
+                    style,
+                ),
+                Span::styled(
+                    format!("{:>8}  ", format_age(entry.created_at)),
+                    Style::new().dim(),
+                ),
+                Span::raw(entry.notification.message.clone()),
+            ]))
+        });
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::current().selected_text);
+        let mut state = ListState::default()
+            .with_selected((!self.history.is_empty()).then_some(self.history_selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+fn lifetime(level: NotificationLevel) -> Duration {
+    match level {
+        NotificationLevel::Info => Duration::from_secs(3),
+        NotificationLevel::Warning => Duration::from_secs(5),
+        NotificationLevel::Error => Duration::from_secs(8),
+    }
+}
+
+/// Returns how many lines `message` takes at `width` columns, by display width since wide
+/// characters such as CJK take two columns.
+fn line_count(message: &str, width: usize) -> usize {
+    message.width().div_ceil(width)
+}
+
+fn level_style(level: NotificationLevel) -> Style {
+    match level {
+        NotificationLevel::Info => theme::current().notification_info,
+        NotificationLevel::Warning => theme::current().notification_warning,
+        NotificationLevel::Error => theme::current().notification_error,
+    }
+}
+
+fn format_age(instant: Instant) -> String {
+    let seconds = instant.elapsed().as_secs();
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h ago", seconds / 3600),
+    }
+}
+
+impl Component for Notifications {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.is_history_open {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Esc => return Ok(Some(Action::ToggleNotificationHistory)),
+            KeyCode::Up => self.history_selected = self.history_selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.history_selected =
+                    (self.history_selected + 1).min(self.history.len().saturating_sub(1))
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => self.expire_toasts(),
+            Action::Notify(notification) => self.push(notification),
+            Action::Error(message) => {
+                self.push(Notification::new(NotificationLevel::Error, message))
+            }
+            Action::ToggleNotificationHistory => return Ok(self.toggle_history()),
+            Action::ChangeMode(mode) => self.mode = mode,
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.is_history_open {
+            self.render_history(frame, area);
+        } else {
+            self.render_toasts(frame, area);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_count_uses_display_width() {
+        assert_eq!(line_count("Joined Standup", 40), 1);
+        assert_eq!(line_count(&"会議".repeat(10), 20), 2);
+        assert_eq!(line_count("", 40), 0);
+    }
+}
@@ -52,6 +52,12 @@ impl Selector2D {
         }
     }
 
+    pub fn select(&mut self, row: usize, col: usize) {
+        assert!(row < self.row_lengths.len());
+        self.selected_row = row;
+        self.selected_col = col.min(self.row_lengths[row].saturating_sub(1));
+    }
+
     pub fn selected(&self) -> (usize, usize) {
         (self.selected_row, self.selected_col)
     }
@@ -0,0 +1,136 @@
+use crate::action::{Action, Mode};
+use crate::persistence::secrets::SharedSecretStore;
+use crate::theme;
+use crate::ui::input::fields::{InputField, PasswordInputField};
+use crate::ui::Component;
+use crate::utils;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use tokio::sync::mpsc::UnboundedSender;
+
+const MAX_PASSPHRASE_LENGTH: usize = 100;
+
+/// Popup asking for the passphrase of the password vault. A vault that does not exist yet is
+/// created with the passphrase entered.
+pub struct UnlockPrompt {
+    command_tx: Option<UnboundedSender<Action>>,
+    secrets: SharedSecretStore,
+    passphrase: PasswordInputField,
+    error: Option<String>,
+    previous_mode: Mode,
+    is_open: bool,
+}
+
+impl UnlockPrompt {
+    pub fn new(secrets: SharedSecretStore) -> Self {
+        Self {
+            command_tx: None,
+            secrets,
+            passphrase: Self::new_passphrase_field(),
+            error: None,
+            previous_mode: Mode::default(),
+            is_open: false,
+        }
+    }
+
+    fn new_passphrase_field() -> PasswordInputField {
+        let mut passphrase =
+            PasswordInputField::new(Some("Passphrase".into()), MAX_PASSPHRASE_LENGTH, None);
+        passphrase.set_cursor_visibility(true);
+        passphrase
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Whether the prompt is closed while there is a locked secret store to unlock.
+    pub fn can_open(&self) -> bool {
+        !self.is_open && self.secrets.borrow().is_locked()
+    }
+
+    /// Opens the prompt, returning to `mode` once it is closed.
+    pub fn open(&mut self, mode: Mode) {
+        self.passphrase = Self::new_passphrase_field();
+        self.error = None;
+        self.previous_mode = mode;
+        self.is_open = true;
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.is_open = false;
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ChangeMode(self.previous_mode))?;
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let passphrase = self.passphrase.get_value();
+        if passphrase.is_empty() {
+            self.error = Some("Enter a passphrase".into());
+            return Ok(());
+        }
+        if let Err(err) = self.secrets.borrow_mut().unlock(&passphrase) {
+            self.error = Some(err.to_string());
+            self.passphrase = Self::new_passphrase_field();
+            return Ok(());
+        }
+        self.close()?;
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::SecretsUnlocked)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for UnlockPrompt {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Enter => self.submit()?,
+            _ => {
+                self.passphrase.handle_key_event(key)?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste(&mut self, text: String) -> Result<Option<Action>> {
+        self.passphrase.handle_paste(text)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+        let title = if self.secrets.borrow().exists() {
+            "Unlock password vault"
+        } else {
+            "Choose a passphrase for the password vault"
+        };
+        let area = utils::centered_rect(area, 60, 6);
+        frame.render_widget(Clear, area);
+        let block = Block::default().borders(Borders::ALL).title(title);
+        frame.render_widget(block.clone(), area);
+
+        let [passphrase_area, message_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Length(1)])
+                .areas(block.inner(area));
+        self.passphrase.draw(frame, passphrase_area)?;
+        let (message, style) = match &self.error {
+            Some(error) => (error.as_str(), theme::current().notification_error),
+            None => ("Enter to unlock, Esc to skip", theme::current().text),
+        };
+        frame.render_widget(Paragraph::new(message).style(style), message_area);
+        Ok(())
+    }
+}
@@ -1,16 +1,23 @@
 mod carousel;
 mod int;
+mod password;
 mod string;
 mod time;
 
+use crate::theme;
 use crate::ui::Component;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use delegate::delegate;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
 use ratatui::Frame;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub use carousel::CarouselInputField;
+pub use int::IntInputField;
+pub use password::{PasswordInputField, PASSWORD_MASK};
 pub use string::StrInputField;
 pub use time::TimeInputField;
 
@@ -31,7 +38,10 @@ pub struct BaseInputField {
     is_cursor_visible: bool,
     border_style: BorderStyle,
     input_handler: Box<dyn InputHandler>,
-    left_padding: u16,
+    // index of the first grapheme in view
+    left_padding: usize,
+    // shown in place of every grapheme when set
+    mask: Option<char>,
 }
 
 impl InputField for BaseInputField {
@@ -56,21 +66,30 @@ impl BaseInputField {
             is_cursor_visible: false,
             input_handler,
             left_padding: 0,
+            mask: None,
         }
     }
 
-    fn recalculate_padding(&mut self, width: u16) {
-        let text_len = self.input_handler.len() as u16;
-        let cursor_pos = self.input_handler.cursor_position() as u16;
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.mask = mask;
+    }
+
+    /// Scrolls so that the cursor stays in view, given the display widths of the graphemes
+    /// and the number of columns available.
+    fn recalculate_padding(&mut self, widths: &[usize], width: usize) {
+        let cursor = self.input_handler.cursor_position().min(widths.len());
+        let columns = |range: Range<usize>| widths[range].iter().sum::<usize>();
 
-        if !(self.left_padding..(self.left_padding + width)).contains(&cursor_pos) {
-            self.left_padding = self
-                .left_padding
-                .max(cursor_pos.saturating_sub(width)) // if cursor is out of right bound set padding to cursor_pos - width
-                .min(cursor_pos); // if cursor is out of left bound set padding to cursor_pos
+        // if cursor is out of left bound, scroll to it
+        self.left_padding = self.left_padding.min(cursor);
+        // if cursor is out of right bound, scroll until there is a free column for it
+        while self.left_padding < cursor && columns(self.left_padding..cursor) >= width {
+            self.left_padding += 1;
+        }
+        // scroll back if there is empty space that is not occupied by the text
+        while self.left_padding > 0 && columns(self.left_padding - 1..widths.len()) < width {
+            self.left_padding -= 1;
         }
-        self.left_padding = self.left_padding.min(text_len.saturating_sub(width));
-        // move padding to the left if there is empty space that is not occupied by the text
     }
 }
 
@@ -81,32 +100,58 @@ impl Component for BaseInputField {
         }
     }
 
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        self.input_handler.paste(&text);
+        Ok(None)
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
         let area = Layout::vertical([Constraint::Length(3)]).split(area)[0];
 
-        self.recalculate_padding(area.width - 3);
+        let width = area.width.saturating_sub(2) as usize;
+        let value = self.input_handler.value();
+        let mask = self.mask.map(String::from);
+        let graphemes: Vec<&str> = match &mask {
+            Some(mask) => value.graphemes(true).map(|_| mask.as_str()).collect(),
+            None => value.graphemes(true).collect(),
+        };
+        let widths: Vec<usize> = graphemes.iter().map(|grapheme| grapheme.width()).collect();
+        self.recalculate_padding(&widths, width);
+        let left_padding = self.left_padding;
 
         if self.is_cursor_visible {
-            frame.set_cursor_position((
-                area.x + 1 + self.input_handler.cursor_position() as u16 - self.left_padding,
-                area.y + 1,
-            ));
+            let cursor = self.input_handler.cursor_position().min(graphemes.len());
+            let cursor_column: usize = widths[left_padding..cursor].iter().sum();
+            frame.set_cursor_position((area.x + 1 + cursor_column as u16, area.y + 1));
         }
 
-        let string_slice_to_show: String = self
-            .input_handler
-            .value()
-            .chars()
-            .skip(self.left_padding as usize)
-            .take(area.width as usize)
-            .collect();
+        let mut used_columns = 0;
+        let visible_count = widths[left_padding..]
+            .iter()
+            .take_while(|&&grapheme_width| {
+                used_columns += grapheme_width;
+                used_columns <= width
+            })
+            .count();
+        let visible = &graphemes[left_padding..left_padding + visible_count];
+        let selection = self.input_handler.selection().unwrap_or_default();
+        let clamp = |index: usize| index.saturating_sub(left_padding).min(visible.len());
+        let (selection_start, selection_end) = (clamp(selection.start), clamp(selection.end));
+        let line = Line::from(vec![
+            Span::raw(visible[..selection_start].concat()),
+            Span::styled(
+                visible[selection_start..selection_end].concat(),
+                theme::current().selected_text,
+            ),
+            Span::raw(visible[selection_end..].concat()),
+        ]);
 
         let block = Block::default()
             .borders(self.border_style.0)
             .border_style(self.border_style.1)
             .title(self.title.clone());
         frame.render_widget(block.clone(), area);
-        frame.render_widget(Line::from(string_slice_to_show), block.inner(area));
+        frame.render_widget(line, block.inner(area));
         Ok(())
     }
 }
@@ -115,85 +160,277 @@ type ValidateFn = Box<dyn Fn(&str) -> bool>;
 
 pub trait InputHandler {
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+    fn paste(&mut self, text: &str);
     fn value(&self) -> String;
     fn cursor_position(&self) -> usize;
-    fn len(&self) -> usize;
+    fn selection(&self) -> Option<Range<usize>>;
 }
 
+/// Edits a single line of text, moving and counting by grapheme so that accented letters,
+/// CJK and emoji are handled as the user sees them.
 pub struct BaseInputHandler {
-    text: Vec<char>,
+    text: Vec<String>,
     cursor: usize,
+    // the other end of the selection, which spans from here to the cursor
+    anchor: Option<usize>,
     max_length: usize,
     validate: ValidateFn,
 }
 
 impl InputHandler for BaseInputHandler {
+    /// Handles the usual line-editing keys. Holding Shift while moving the cursor selects
+    /// text, and words are runs of letters and digits so that Ctrl-Left/Right and Ctrl-W
+    /// stop at the separators in links.
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
         match key.code {
-            KeyCode::Right => self.try_move_cursor_right(),
-            KeyCode::Left => self.try_move_cursor_left(),
-            KeyCode::Char(c) => self.try_insert_char(c),
-            KeyCode::Backspace => self.backspace(),
+            KeyCode::Left if ctrl => self.move_cursor(self.prev_word_start(), shift),
+            KeyCode::Right if ctrl => self.move_cursor(self.next_word_end(), shift),
+            KeyCode::Left => match self.selection() {
+                Some(selection) if !shift => self.move_cursor(selection.start, false),
+                _ => self.move_cursor(self.cursor.saturating_sub(1), shift),
+            },
+            KeyCode::Right => match self.selection() {
+                Some(selection) if !shift => self.move_cursor(selection.end, false),
+                _ => self.move_cursor((self.cursor + 1).min(self.text.len()), shift),
+            },
+            KeyCode::Home => self.move_cursor(0, shift),
+            KeyCode::End => self.move_cursor(self.text.len(), shift),
+            KeyCode::Char('w') if ctrl => {
+                self.delete_selection_or(self.prev_word_start()..self.cursor)
+            }
+            KeyCode::Char('u') if ctrl => self.delete(0..self.cursor),
+            KeyCode::Char('k') if ctrl => self.delete(self.cursor..self.text.len()),
+            KeyCode::Char(_) if ctrl || key.modifiers.contains(KeyModifiers::ALT) => (),
+            KeyCode::Char(c) => self.try_insert(&c.to_string()),
+            KeyCode::Backspace => {
+                self.delete_selection_or(self.cursor.saturating_sub(1)..self.cursor)
+            }
+            KeyCode::Delete => {
+                self.delete_selection_or(self.cursor..(self.cursor + 1).min(self.text.len()))
+            }
             _ => (),
         };
         Ok(None)
     }
 
+    /// Inserts `text` at the cursor with line breaks removed, cutting it to fit into
+    /// `max_length`. Nothing is inserted if the result would not pass validation.
+    fn paste(&mut self, text: &str) {
+        let pasted: String = text.chars().filter(|c| !matches!(c, '\n' | '\r')).collect();
+        self.try_insert(&pasted);
+    }
+
     fn value(&self) -> String {
-        self.text.iter().collect()
+        self.text.concat()
     }
 
     fn cursor_position(&self) -> usize {
         self.cursor
     }
 
-    fn len(&self) -> usize {
-        self.text.len()
+    fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let selection = anchor.min(self.cursor)..anchor.max(self.cursor);
+        (!selection.is_empty()).then_some(selection)
     }
 }
 
 impl BaseInputHandler {
     pub fn new(initial: Option<String>, max_length: usize, validate: Option<ValidateFn>) -> Self {
-        let initial_text: Vec<char> = initial
+        let initial_text: Vec<String> = initial
             .unwrap_or_default()
-            .chars()
+            .graphemes(true)
             .take(max_length)
+            .map(String::from)
             .collect();
         Self {
             max_length,
             cursor: initial_text.len(),
+            anchor: None,
             text: initial_text,
             validate: validate.unwrap_or(Box::new(|_| true)),
         }
     }
 
-    fn try_move_cursor_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor = self.cursor.saturating_sub(1);
+    /// Moves the cursor to `position`, extending the selection if `select` is set and
+    /// dropping it otherwise.
+    fn move_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
         }
+        self.cursor = position;
+    }
+
+    fn is_word_char(&self, index: usize) -> bool {
+        self.text[index]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric)
     }
 
-    fn try_move_cursor_right(&mut self) {
-        if self.cursor < self.text.len() {
-            self.cursor = self.cursor.saturating_add(1);
+    fn prev_word_start(&self) -> usize {
+        let mut position = self.cursor;
+        while position > 0 && !self.is_word_char(position - 1) {
+            position -= 1;
         }
+        while position > 0 && self.is_word_char(position - 1) {
+            position -= 1;
+        }
+        position
     }
 
-    fn try_insert_char(&mut self, c: char) {
-        if self.text.len() < self.max_length {
-            let mut new_value = self.text.clone();
-            new_value.insert(self.cursor, c);
-            if (self.validate)(&new_value.iter().collect::<String>()) {
-                self.text = new_value;
-                self.cursor += 1;
-            }
+    fn next_word_end(&self) -> usize {
+        let mut position = self.cursor;
+        while position < self.text.len() && !self.is_word_char(position) {
+            position += 1;
+        }
+        while position < self.text.len() && self.is_word_char(position) {
+            position += 1;
         }
+        position
     }
 
-    fn backspace(&mut self) {
-        if self.cursor > 0 {
-            self.text.remove(self.cursor - 1);
-            self.cursor -= 1;
+    /// Replaces the selection, or inserts at the cursor if there is none.
+    fn try_insert(&mut self, text: &str) {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        let inserted: Vec<&str> = text.graphemes(true).collect();
+        let (before, after) = (
+            self.text[..range.start].concat(),
+            self.text[range.end..].concat(),
+        );
+        // The inserted text may combine with its neighbours, so more graphemes than there is
+        // room for can fit, by one at either end.
+        let room = self.max_length - (self.text.len() - range.len());
+        for count in (0..=inserted.len().min(room + 2)).rev() {
+            if count == 0 && range.is_empty() {
+                return;
+            }
+            let before_cursor = before.clone() + &inserted[..count].concat();
+            let new_value = before_cursor.clone() + &after;
+            let new_text: Vec<String> = new_value.graphemes(true).map(String::from).collect();
+            if new_text.len() <= self.max_length {
+                if (self.validate)(&new_value) {
+                    self.cursor = before_cursor.graphemes(true).count().min(new_text.len());
+                    self.text = new_text;
+                    self.anchor = None;
+                }
+                return;
+            }
         }
     }
+
+    fn delete(&mut self, range: Range<usize>) {
+        self.text.drain(range.clone());
+        self.cursor = range.start;
+        self.anchor = None;
+    }
+
+    /// Deletes the selected text if there is any, and `range` otherwise.
+    fn delete_selection_or(&mut self, range: Range<usize>) {
+        self.delete(self.selection().unwrap_or(range));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn press(handler: &mut BaseInputHandler, code: KeyCode, modifiers: KeyModifiers) {
+        handler
+            .handle_key_event(KeyEvent::new(code, modifiers))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_paste_inserts_at_cursor() {
+        let mut handler = BaseInputHandler::new(Some("https://".into()), 50, None);
+        press(&mut handler, KeyCode::Left, KeyModifiers::NONE);
+        handler.paste("zoom.us/j/1\r\n");
+        assert_eq!(handler.value(), "https:/zoom.us/j/1/");
+        assert_eq!(handler.cursor_position(), 18);
+    }
+
+    #[test]
+    fn test_paste_respects_max_length_and_validator() {
+        let mut handler = BaseInputHandler::new(None, 5, None);
+        handler.paste("1234567");
+        assert_eq!(handler.value(), "12345");
+
+        let digits_only: ValidateFn = Box::new(|s: &str| s.chars().all(|c| c.is_ascii_digit()));
+        let mut handler = BaseInputHandler::new(None, 5, Some(digits_only));
+        handler.paste("12a");
+        assert_eq!(handler.value(), "");
+    }
+
+    #[test]
+    fn test_cursor_motion() {
+        let mut handler = BaseInputHandler::new(Some("meet.google.com/abc".into()), 50, None);
+        press(&mut handler, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(handler.cursor_position(), 0);
+        press(&mut handler, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(handler.cursor_position(), 4);
+        press(&mut handler, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(handler.cursor_position(), 11);
+        press(&mut handler, KeyCode::End, KeyModifiers::NONE);
+        press(&mut handler, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(handler.cursor_position(), 16);
+    }
+
+    #[test]
+    fn test_delete_and_kill_commands() {
+        let mut handler = BaseInputHandler::new(Some("zoom.us/j/123".into()), 50, None);
+        press(&mut handler, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(handler.value(), "zoom.us/j/");
+        press(&mut handler, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut handler, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(handler.value(), "oom.us/j/");
+        press(&mut handler, KeyCode::Right, KeyModifiers::CONTROL);
+        press(&mut handler, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(handler.value(), "oom");
+        press(&mut handler, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut handler, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(handler.value(), "m");
+        assert_eq!(handler.cursor_position(), 0);
+    }
+
+    #[test]
+    fn test_shift_selection() {
+        let mut handler = BaseInputHandler::new(Some("teams link".into()), 50, None);
+        press(
+            &mut handler,
+            KeyCode::Left,
+            KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+        );
+        assert_eq!(handler.selection(), Some(6..10));
+        press(&mut handler, KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(handler.value(), "teams x");
+        assert_eq!(handler.selection(), None);
+
+        press(&mut handler, KeyCode::Home, KeyModifiers::SHIFT);
+        press(&mut handler, KeyCode::Right, KeyModifiers::SHIFT);
+        assert_eq!(handler.selection(), Some(1..7));
+        press(&mut handler, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(handler.value(), "t");
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let mut handler = BaseInputHandler::new(Some("Встреча 会議 👩‍💻".into()), 50, None);
+        assert_eq!(handler.text.len(), 12);
+        press(&mut handler, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(handler.value(), "Встреча 会議 ");
+        press(&mut handler, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(handler.cursor_position(), 8);
+
+        let mut handler = BaseInputHandler::new(Some("e".into()), 1, None);
+        handler.paste("\u{301}");
+        assert_eq!(handler.value(), "e\u{301}");
+        assert_eq!(handler.cursor_position(), 1);
+    }
 }
@@ -4,7 +4,7 @@ use crate::ui::input::fields::{BorderStyle, InputField};
 use crate::ui::Component;
 use crate::utils;
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::{Line, Style};
 use ratatui::widgets::{Block, Borders};
@@ -37,6 +37,14 @@ impl Component for CarouselInputField {
         };
         Ok(None)
     }
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.selector.next(),
+            MouseEventKind::ScrollDown => self.selector.prev(),
+            _ => (),
+        };
+        Ok(None)
+    }
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let area = Layout::vertical([Constraint::Length(3)]).split(area)[0];
         let block = Block::default()
@@ -50,7 +58,7 @@ impl Component for CarouselInputField {
                 "<{}>",
                 utils::center_text(
                     &self.options[self.selector.index],
-                    area.width as usize - 2 - 2,
+                    (area.width as usize).saturating_sub(2 + 2),
                     ' '
                 )
             )),
@@ -7,6 +7,7 @@ use crossterm::event::KeyEvent;
 use delegate::delegate;
 use ratatui::layout::Rect;
 use ratatui::Frame;
+use std::ops::Range;
 
 pub struct IntInputHandler(BaseInputHandler);
 
@@ -34,8 +35,9 @@ impl InputHandler for IntInputHandler {
     delegate! {
         to self.0 {
             fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+            fn paste(&mut self, text: &str);
             fn cursor_position(&self) -> usize;
-            fn len(&self) -> usize;
+            fn selection(&self) -> Option<Range<usize>>;
         }
     }
 }
@@ -51,7 +53,6 @@ impl InputField for IntInputField {
         }
     }
 }
-#[allow(dead_code)]
 impl IntInputField {
     pub fn new(title: Option<String>, max: u32, initial_number: Option<u32>) -> Self {
         Self(BaseInputField::new(
@@ -65,6 +66,7 @@ impl Component for IntInputField {
     delegate! {
         to self.0 {
             fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+            fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>>;
             fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()>;
         }
     }
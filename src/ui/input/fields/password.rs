@@ -0,0 +1,69 @@
+use crate::action::Action;
+use crate::ui::input::fields::{BaseInputField, BaseInputHandler, BorderStyle, InputField};
+use crate::ui::Component;
+use crossterm::event::KeyEvent;
+use delegate::delegate;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+/// Character shown in place of every character of a hidden password.
+pub const PASSWORD_MASK: char = '\u{2022}';
+
+/// Text field that shows bullets instead of its value until it is revealed with
+/// `Action::TogglePasswordVisibility`. It is hidden again once it stops being edited.
+pub struct PasswordInputField {
+    field: BaseInputField,
+    is_revealed: bool,
+}
+
+impl PasswordInputField {
+    pub fn new(title: Option<String>, max_length: usize, initial_text: Option<String>) -> Self {
+        let mut field = BaseInputField::new(
+            title,
+            Box::new(BaseInputHandler::new(initial_text, max_length, None)),
+        );
+        field.set_mask(Some(PASSWORD_MASK));
+        Self {
+            field,
+            is_revealed: false,
+        }
+    }
+
+    fn set_revealed(&mut self, revealed: bool) {
+        self.is_revealed = revealed;
+        self.field.set_mask((!revealed).then_some(PASSWORD_MASK));
+    }
+}
+
+impl InputField for PasswordInputField {
+    delegate! {
+        to self.field {
+            fn get_value(&self) -> String;
+            fn borders(&mut self, border_style: BorderStyle);
+        }
+    }
+
+    fn set_cursor_visibility(&mut self, visible: bool) {
+        if !visible {
+            self.set_revealed(false);
+        }
+        self.field.set_cursor_visibility(visible);
+    }
+}
+
+impl Component for PasswordInputField {
+    delegate! {
+        to self.field {
+            fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+            fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>>;
+            fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()>;
+        }
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        if action == Action::TogglePasswordVisibility {
+            self.set_revealed(!self.is_revealed);
+        }
+        Ok(None)
+    }
+}
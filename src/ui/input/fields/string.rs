@@ -31,6 +31,7 @@ impl Component for StrInputField {
     delegate! {
         to self.0 {
             fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+            fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>>;
             fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()>;
         }
     }
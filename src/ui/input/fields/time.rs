@@ -1,6 +1,6 @@
 use crate::action::Action;
 use crate::entities::Time;
-use crate::theme::THEME;
+use crate::theme;
 use crate::ui::input::fields::int::IntInputHandler;
 use crate::ui::input::fields::{BorderStyle, InputField, InputHandler};
 use crate::ui::Component;
@@ -112,8 +112,8 @@ impl Component for TimeInputField {
         let mut hours = Span::raw(format!("{:02}", hours));
         if self.is_cursor_visible {
             match self.selected_field {
-                SelectedField::Hours => hours.style = THEME.selected_text,
-                SelectedField::Minutes => minutes.style = THEME.selected_text,
+                SelectedField::Hours => hours.style = theme::current().selected_text,
+                SelectedField::Minutes => minutes.style = theme::current().selected_text,
             };
         }
 
@@ -1,18 +1,28 @@
-use crate::action::Action;
+use crate::action::{Action, Notification, NotificationLevel};
 use crate::entities::{Conference, Week};
-use crate::theme::THEME;
-use crate::ui::input::fields::{CarouselInputField, InputField, StrInputField, TimeInputField};
+use crate::launcher::MeetingLink;
+use crate::ui::input::fields::{
+    CarouselInputField, InputField, PasswordInputField, StrInputField, TimeInputField,
+};
 use crate::ui::input::forms::Form;
-use crate::ui::Component;
-use crossterm::event::KeyEvent;
+use crate::ui::{Command, Component};
+use crossterm::event::{KeyEvent, MouseEvent};
 use delegate::delegate;
 use ratatui::prelude::*;
 use std::str::FromStr;
 
-pub struct ConferenceEditForm(Form);
+pub struct ConferenceEditForm {
+    form: Form,
+    // the conference being edited, for what the form does not show
+    conference: Conference,
+    // the link as of the last input, to notice when it changes
+    last_link: String,
+}
 
 impl ConferenceEditForm {
     const AUTOSTART_PERMISSION_OPTIONS: [&'static str; 2] = ["Deny", "Allow"];
+    const LINK_ROW: usize = 2;
+    const PASSWORD_ROW: usize = 3;
 
     pub fn new(conference: Option<Conference>) -> Self {
         let conference = conference.unwrap_or_default();
@@ -21,7 +31,7 @@ impl ConferenceEditForm {
                 Box::new(StrInputField::new(
                     Some("Title".into()),
                     50,
-                    Some(conference.title),
+                    Some(conference.title.clone()),
                 )),
                 50,
             )],
@@ -29,14 +39,14 @@ impl ConferenceEditForm {
                 (
                     Box::new(TimeInputField::new(
                         Some("Start Time".into()),
-                        Some(conference.start_time),
+                        Some(conference.start_time.clone()),
                     )),
                     25,
                 ),
                 (
                     Box::new(TimeInputField::new(
                         Some("End Time".into()),
-                        Some(conference.end_time),
+                        Some(conference.end_time.clone()),
                     )),
                     25,
                 ),
@@ -45,16 +55,12 @@ impl ConferenceEditForm {
                 Box::new(StrInputField::new(
                     Some("Link".into()),
                     50,
-                    Some(conference.link),
+                    Some(conference.link.clone()),
                 )),
                 50,
             )],
             vec![(
-                Box::new(StrInputField::new(
-                    Some("Password".into()),
-                    50,
-                    Some(conference.password.unwrap_or_default()),
-                )),
+                Self::new_password_field(Some(conference.password.clone().unwrap_or_default())),
                 50,
             )],
             vec![(
@@ -78,17 +84,66 @@ impl ConferenceEditForm {
                 )),
                 50,
             )],
+            vec![(
+                Box::new(StrInputField::new(
+                    Some("Tags (comma separated)".into()),
+                    100,
+                    Some(conference.tags.join(", ")),
+                )),
+                50,
+            )],
         ];
-        Self(
-            Form::new(field_layout)
-                .with_field_style(THEME.input_field)
-                .with_selected_field_style(THEME.selected_field)
-                .with_active_field_style(THEME.active_field),
-        )
+        Self {
+            form: Form::new(field_layout),
+            last_link: conference.link.clone(),
+            conference,
+        }
+    }
+
+    fn new_password_field(password: Option<String>) -> Box<dyn InputField> {
+        Box::new(PasswordInputField::new(
+            Some("Password".into()),
+            50,
+            password,
+        ))
+    }
+
+    /// Fills in the password with the passcode embedded in the link when the link changed and
+    /// no password was entered yet.
+    fn fill_in_passcode(&mut self) -> Option<Action> {
+        let input = self.form.get_input();
+        let link = &input[Self::LINK_ROW][0];
+        if *link == self.last_link {
+            return None;
+        }
+        self.last_link = link.clone();
+        if !input[Self::PASSWORD_ROW][0].is_empty() {
+            return None;
+        }
+        let passcode = MeetingLink::parse(link).ok()?.passcode?;
+        self.form.replace_field(
+            Self::PASSWORD_ROW,
+            0,
+            Self::new_password_field(Some(passcode)),
+        );
+        Some(Action::Notify(Notification::new(
+            NotificationLevel::Info,
+            "Filled in the passcode from the link",
+        )))
+    }
+
+    pub fn commands() -> Vec<Command> {
+        let mut commands = Form::commands();
+        commands.push(Command::new(
+            "Show or hide password",
+            Action::TogglePasswordVisibility,
+        ));
+        commands.push(Command::new("Save and close", Action::CloseForm));
+        commands
     }
 
     pub fn get_conference(&self) -> Conference {
-        let input = self.0.get_input();
+        let input = self.form.get_input();
         Conference {
             title: input[0][0].clone(),
             start_time: input[1][0]
@@ -97,21 +152,39 @@ impl ConferenceEditForm {
             end_time: input[1][1]
                 .parse()
                 .expect("TimeInputField should give valid time"),
-            link: input[2][0].clone(),
-            password: (!input[3][0].is_empty()).then_some(input[3][0].clone()),
+            link: input[Self::LINK_ROW][0].clone(),
+            password: (!input[Self::PASSWORD_ROW][0].is_empty())
+                .then_some(input[Self::PASSWORD_ROW][0].clone()),
             autostart_permission: input[4][0] == Self::AUTOSTART_PERMISSION_OPTIONS[1],
             week: Week::from_str(&input[5][0])
                 .expect("Week input field should always give a valid week"),
+            tags: input[6][0]
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(String::from)
+                .collect(),
+            ..self.conference.clone()
         }
     }
 }
 
 impl Component for ConferenceEditForm {
     delegate! {
-        to self.0 {
-             fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>>;
+        to self.form {
+             fn handle_mouse_event(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>>;
              fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>>;
              fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()>;
         }
     }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        let action = self.form.handle_key_event(key)?;
+        Ok(action.or_else(|| self.fill_in_passcode()))
+    }
+
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        let action = self.form.handle_paste(text)?;
+        Ok(action.or_else(|| self.fill_in_passcode()))
+    }
 }
@@ -1,8 +1,9 @@
 use crate::action::Action;
+use crate::theme;
 use crate::ui::components::Selector2D;
 use crate::ui::input::fields::InputField;
-use crate::ui::Component;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::ui::{Command, Component};
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::prelude::*;
 use ratatui::widgets::Borders;
 
@@ -10,9 +11,8 @@ pub struct Form {
     is_selected_field_active: bool,
     selector: Selector2D,
     layout: Vec<Vec<(Box<dyn InputField>, u16)>>,
-    field_style: Style,
-    selected_field_style: Style,
-    active_field_style: Style,
+    // where the fields were last drawn, for mouse hit testing
+    field_areas: Vec<Vec<Rect>>,
 }
 
 impl Form {
@@ -29,12 +29,20 @@ impl Form {
             is_selected_field_active: false,
             selector: Selector2D::new(layout.iter().map(|row| row.len()).collect()),
             layout,
-            field_style: Style::default(),
-            selected_field_style: Style::default(),
-            active_field_style: Style::default(),
+            field_areas: Vec::new(),
         }
     }
 
+    /// Returns the row and column of the selected field.
+    pub fn selected(&self) -> (usize, usize) {
+        self.selector.selected()
+    }
+
+    /// Whether the selected field is being edited.
+    pub fn is_editing(&self) -> bool {
+        self.is_selected_field_active
+    }
+
     pub fn get_input(&self) -> Vec<Vec<String>> {
         self.layout
             .iter()
@@ -42,36 +50,49 @@ impl Form {
             .collect()
     }
 
-    //region style setters
-    pub fn with_field_style(mut self, style: Style) -> Self {
-        self.field_style = style;
-        self
+    /// Replaces the field at `row` and `col`, keeping its width.
+    pub fn replace_field(&mut self, row: usize, col: usize, field: Box<dyn InputField>) {
+        self.layout[row][col].0 = field;
     }
 
-    pub fn with_selected_field_style(mut self, style: Style) -> Self {
-        self.selected_field_style = style;
-        self
+    pub fn commands() -> Vec<Command> {
+        vec![
+            Command::new("Start or stop editing a field", Action::ToggleField),
+            Command::new("Focus field above", Action::FocusUp),
+            Command::new("Focus field below", Action::FocusDown),
+            Command::new("Focus field to the left", Action::FocusLeft),
+            Command::new("Focus field to the right", Action::FocusRight),
+        ]
     }
 
-    pub fn with_active_field_style(mut self, style: Style) -> Self {
-        self.active_field_style = style;
-        self
+    fn handle_field_selection(&mut self, action: Action) {
+        match action {
+            Action::FocusUp => self.selector.move_up(),
+            Action::FocusDown => self.selector.move_down(),
+            Action::FocusLeft => self.selector.move_left(),
+            Action::FocusRight => self.selector.move_right(),
+            _ => {}
+        }
     }
-    //endregion style setters
 
-    fn handle_field_selection(&mut self, key: KeyEvent) {
-        let field_style = self.field_style;
-        self.selected_field().borders((Borders::ALL, field_style));
-        match key.code {
-            KeyCode::Up => self.selector.move_up(),
-            KeyCode::Down => self.selector.move_down(),
-            KeyCode::Left => self.selector.move_left(),
-            KeyCode::Right => self.selector.move_right(),
-            _ => {}
+    /// Returns the row and column of the field drawn at `position`.
+    fn field_at(&self, position: Position) -> Option<(usize, usize)> {
+        self.field_areas
+            .iter()
+            .enumerate()
+            .find_map(|(row, areas)| {
+                let col = areas.iter().position(|area| area.contains(position))?;
+                Some((row, col))
+            })
+    }
+
+    /// Selects the field at `row` and `col` and starts editing it.
+    fn focus_field(&mut self, row: usize, col: usize) {
+        if self.is_selected_field_active {
+            self.toggle_selected_field_activeness();
         }
-        let selected_field_style = self.selected_field_style;
-        self.selected_field()
-            .borders((Borders::ALL, selected_field_style))
+        self.selector.select(row, col);
+        self.toggle_selected_field_activeness();
     }
 
     fn propagate_key(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
@@ -105,45 +126,65 @@ impl Form {
         let is_active = !self.is_selected_field_active;
 
         self.is_selected_field_active = is_active;
-        let new_style = if self.is_selected_field_active {
-            self.active_field_style
-        } else {
-            self.selected_field_style
-        };
-
-        self.selected_field().borders((Borders::ALL, new_style));
         self.selected_field().set_cursor_visibility(is_active);
     }
 }
 
 impl Component for Form {
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
-        match key.code {
-            KeyCode::Enter => self.toggle_selected_field_activeness(),
-            _ => {
-                if self.is_selected_field_active {
-                    self.propagate_key(key)?;
-                } else {
-                    self.handle_field_selection(key);
-                }
-            }
+        self.propagate_key(key)
+    }
+
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        if self.is_selected_field_active {
+            self.selected_field().handle_paste(text)?;
         }
         Ok(None)
     }
 
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+        let Some((row, col)) = self.field_at(Position::new(mouse.column, mouse.row)) else {
+            return Ok(None);
+        };
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+            && (self.selector.selected() != (row, col) || !self.is_selected_field_active)
+        {
+            self.focus_field(row, col);
+        }
+        self.layout[row][col].0.handle_mouse_event(mouse)
+    }
+
     fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
-        if self.is_selected_field_active {
-            self.selected_field().update(action)?;
+        match action {
+            Action::ToggleField => self.toggle_selected_field_activeness(),
+            Action::FocusUp | Action::FocusDown | Action::FocusLeft | Action::FocusRight
+                if !self.is_selected_field_active =>
+            {
+                self.handle_field_selection(action)
+            }
+            _ if self.is_selected_field_active => {
+                self.selected_field().update(action)?;
+            }
+            _ => {}
         }
         Ok(None)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
-        let layout = self.build_layout(area);
+        self.field_areas = self.build_layout(area);
 
+        // styles are read on every draw so that a theme switch shows up in open forms
+        let theme = theme::current();
+        let selected = self.selector.selected();
         for (row_index, row) in self.layout.iter_mut().enumerate() {
             for (col_index, (field, _)) in row.iter_mut().enumerate() {
-                field.draw(frame, layout[row_index][col_index])?;
+                let style = match (row_index, col_index) == selected {
+                    true if self.is_selected_field_active => theme.active_field,
+                    true => theme.selected_field,
+                    false => theme.input_field,
+                };
+                field.borders((Borders::ALL, style));
+                field.draw(frame, self.field_areas[row_index][col_index])?;
             }
         }
         Ok(())
@@ -1,22 +1,30 @@
 use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
 use std::cell::RefCell;
 use std::rc::Rc;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Mode;
-use crate::entities::{Schedule, Settings};
-use crate::ui::components::FpsCounter;
+use crate::config::key_sequence_to_string;
+use crate::entities::{JoinRecord, Schedule, Settings};
+use crate::persistence::secrets::SharedSecretStore;
+use crate::theme;
+use crate::ui::components::{CommandPalette, FpsCounter, HelpOverlay, UnlockPrompt};
 use crate::ui::pages::{SchedulePage, SettingsPage};
-use crate::ui::Component;
+use crate::ui::{global_commands, Command, Component};
 use crate::{action::Action, config::Config};
 
 pub struct Home {
     schedule: SchedulePage,
     settings: SettingsPage,
     fps: FpsCounter,
+    command_palette: CommandPalette,
+    help: HelpOverlay,
+    unlock_prompt: UnlockPrompt,
     active_page: ActivePage,
+    mode: Mode,
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
 }
@@ -29,55 +37,161 @@ enum ActivePage {
 }
 
 impl Home {
-    pub fn new(schedule: Rc<RefCell<Schedule>>, settings: Rc<RefCell<Settings>>) -> Self {
+    pub fn new(
+        schedule: Rc<RefCell<Schedule>>,
+        settings: Rc<RefCell<Settings>>,
+        secrets: SharedSecretStore,
+        history: Rc<RefCell<Vec<JoinRecord>>>,
+    ) -> Self {
         Self {
-            schedule: SchedulePage::new(schedule),
+            schedule: SchedulePage::new(schedule, history),
             settings: SettingsPage::new(settings),
             fps: FpsCounter::default(),
+            command_palette: CommandPalette::default(),
+            help: HelpOverlay::default(),
+            unlock_prompt: UnlockPrompt::new(secrets),
             active_page: ActivePage::default(),
+            mode: Mode::default(),
             command_tx: None,
             config: Config::default(),
         }
     }
+
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let style = theme::current().status_bar;
+        let hint = self
+            .config
+            .keybindings
+            .get(&self.mode)
+            .into_iter()
+            .flatten()
+            .filter(|(_, action)| **action == Action::Help)
+            .map(|(keys, _)| format!("{} help ", key_sequence_to_string(keys)))
+            .min()
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(format!(" {:?}", self.mode)).style(style),
+            area,
+        );
+        frame.render_widget(Paragraph::new(hint).style(style).right_aligned(), area);
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        let mut commands = global_commands();
+        commands.extend(match self.active_page {
+            ActivePage::Schedule => self.schedule.commands(),
+            ActivePage::Settings => self.settings.commands(),
+        });
+        commands
+    }
 }
 
 impl Component for Home {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_palette.register_action_handler(tx.clone())?;
+        self.unlock_prompt.register_action_handler(tx.clone())?;
+        self.schedule.register_action_handler(tx.clone())?;
         self.command_tx = Some(tx);
         Ok(())
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.command_palette
+            .register_config_handler(config.clone())?;
+        self.schedule.register_config_handler(config.clone())?;
         self.config = config;
         Ok(())
     }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.unlock_prompt.is_open() {
+            return self.unlock_prompt.handle_key_event(key);
+        }
+        if self.command_palette.is_open() {
+            return self.command_palette.handle_key_event(key);
+        }
+        if self.help.is_open() {
+            return self.help.handle_key_event(key);
+        }
         match self.active_page {
             ActivePage::Settings => Ok(self.settings.handle_key_event(key)?),
             ActivePage::Schedule => Ok(self.schedule.handle_key_event(key)?),
         }
     }
 
+    fn handle_paste(&mut self, text: String) -> Result<Option<Action>> {
+        if self.unlock_prompt.is_open() {
+            return self.unlock_prompt.handle_paste(text);
+        }
+        if self.command_palette.is_open() {
+            return self.command_palette.handle_paste(text);
+        }
+        if self.help.is_open() {
+            return Ok(None);
+        }
+        match self.active_page {
+            ActivePage::Settings => self.settings.handle_paste(text),
+            ActivePage::Schedule => self.schedule.handle_paste(text),
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if self.command_palette.is_open() || self.help.is_open() || self.unlock_prompt.is_open() {
+            return Ok(None);
+        }
+        match self.active_page {
+            ActivePage::Settings => self.settings.handle_mouse_event(mouse),
+            ActivePage::Schedule => self.schedule.handle_mouse_event(mouse),
+        }
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         self.fps.update(action.clone())?;
-        self.schedule.update(action.clone())?;
-        self.settings.update(action.clone())?;
-        if let Action::ChangeMode(mode) = action {
-            match mode {
-                Mode::Settings => {
-                    self.active_page = ActivePage::Settings;
-                }
-                Mode::Schedule => {
-                    self.active_page = ActivePage::Schedule;
+        let page_action = match self.active_page {
+            ActivePage::Schedule => self.schedule.update(action.clone())?,
+            ActivePage::Settings => self.settings.update(action.clone())?,
+        };
+        match action {
+            Action::OpenCommandPalette if !self.command_palette.is_open() => {
+                self.command_palette.open(self.commands(), self.mode);
+                return Ok(Some(Action::ChangeMode(Mode::Palette)));
+            }
+            Action::UnlockSecrets if self.unlock_prompt.can_open() => {
+                self.unlock_prompt.open(self.mode);
+                return Ok(Some(Action::ChangeMode(Mode::Unlock)));
+            }
+            Action::Help if self.help.is_open() => {
+                return Ok(Some(Action::ChangeMode(self.help.close())));
+            }
+            Action::Help => {
+                let (page, hints) = match self.active_page {
+                    ActivePage::Schedule => ("Schedule page", self.schedule.key_hints()),
+                    ActivePage::Settings => ("Settings page", Vec::new()),
+                };
+                self.help.open(self.mode, &self.commands(), page, hints);
+                return Ok(Some(Action::ChangeMode(Mode::Help)));
+            }
+            Action::ChangeMode(mode) => {
+                self.mode = mode;
+                match mode {
+                    Mode::Settings => {
+                        self.active_page = ActivePage::Settings;
+                    }
+                    Mode::Schedule => {
+                        self.active_page = ActivePage::Schedule;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+            _ => {}
         }
-        Ok(None)
+        Ok(page_action)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [area, status_bar_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+        self.render_status_bar(frame, status_bar_area);
         match self.active_page {
             ActivePage::Settings => {
                 self.settings.draw(frame, area)?;
@@ -88,6 +202,9 @@ impl Component for Home {
                 self.fps.draw(frame, area)?;
             }
         }
+        self.help.draw(frame, area)?;
+        self.command_palette.draw(frame, area)?;
+        self.unlock_prompt.draw(frame, area)?;
         Ok(())
     }
 }
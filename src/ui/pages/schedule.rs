@@ -1,16 +1,34 @@
-use crate::action::{Action, Mode as AppMode};
-use crate::entities::Schedule;
-use crate::theme::THEME;
+use crate::action::{Action, Mode as AppMode, Notification, NotificationLevel};
+use crate::clipboard;
+use crate::config::Config;
+use crate::entities::{Conference, JoinRecord, Schedule, Time};
+use crate::launcher::MeetingLink;
+use crate::theme;
 use crate::ui::components::Selector2D;
+use crate::ui::input::fields::PASSWORD_MASK;
 use crate::ui::input::forms::ConferenceEditForm;
-use crate::ui::Component;
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::prelude::{Color, Style};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Tabs};
+use crate::ui::{Command, Component, KeyHint};
+use chrono::{Datelike, Local};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Constraint, Layout, Margin, Position, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap};
 use ratatui::Frame;
+use search::ConferenceSearch;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+mod search;
+
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const DAY_TAB_WIDTH: u16 = 7;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const DETAILS_WIDTH: u16 = 45;
+// fixed length, so the mask does not give away how long the password is
+const MASKED_PASSWORD_LENGTH: usize = 8;
 
 #[derive(Default)]
 enum Mode {
@@ -18,119 +36,487 @@ enum Mode {
     View,
     Edit(ConferenceEditForm),
     Add(ConferenceEditForm),
+    Search(Box<ConferenceSearch>),
 }
 
 pub struct SchedulePage {
     schedule: Rc<RefCell<Schedule>>,
+    history: Rc<RefCell<Vec<JoinRecord>>>,
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
     selector: Selector2D,
     mode: Mode,
+    // where the day tabs and the conference list were last drawn, for mouse hit testing
+    tabs_area: Rect,
+    list_area: Rect,
+    list_offset: usize,
+    // the conference selected when the list was last drawn, kept selected on reload
+    selected_id: Option<Uuid>,
+    last_click: Option<(Instant, (usize, usize))>,
+    // the conference whose password is shown in the details, hidden again on selection change
+    revealed_password: Option<(usize, usize)>,
 }
 
 impl SchedulePage {
-    pub fn new(schedule: Rc<RefCell<Schedule>>) -> Self {
+    pub fn new(schedule: Rc<RefCell<Schedule>>, history: Rc<RefCell<Vec<JoinRecord>>>) -> Self {
         let day_lengths = schedule.borrow().get_conference_count_by_day();
         Self {
             selector: Selector2D::new(day_lengths),
             schedule,
+            history,
+            command_tx: None,
+            config: Config::default(),
             mode: Mode::default(),
+            tabs_area: Rect::default(),
+            list_area: Rect::default(),
+            list_offset: 0,
+            selected_id: None,
+            last_click: None,
+            revealed_password: None,
         }
     }
 
     fn render_days(&mut self, frame: &mut Frame, area: Rect) {
-        let titles =
-            ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].map(|day| format!("  {}  ", day));
+        self.tabs_area = area;
+        let titles = DAY_NAMES.map(|day| format!("  {}  ", day));
         let (selected_day, _) = self.selector.selected();
         let tabs = Tabs::new(titles)
             .block(Block::default().borders(Borders::ALL).title("Schedule"))
-            .style(Style::default().fg(Color::White))
+            .style(theme::current().text)
             .divider("")
             .padding("", "")
-            .highlight_style(THEME.selected_text)
+            .highlight_style(theme::current().selected_text)
             .select(selected_day);
         frame.render_widget(tabs, area);
     }
 
     fn render_conferences(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = theme::current();
         let (selected_day, selected_conference) = self.selector.selected();
-        let titles: Vec<String> = self
-            .schedule
-            .borrow()
-            .get_day(selected_day)
-            .iter()
-            .map(|c| c.title.clone())
-            .collect();
-        let items = titles.into_iter().map(ListItem::new);
-        let list = List::new(items).highlight_style(THEME.selected_text);
+        let now = Local::now();
+        let is_today = now.weekday().num_days_from_monday() as usize == selected_day;
+        let (time, iso_week) = (Time::now(), now.iso_week().week());
+
+        let schedule = self.schedule.borrow();
+        let conferences = schedule.get_day(selected_day);
+        let items = conferences.iter().enumerate().map(|(index, conference)| {
+            let has_conflict = conferences
+                .iter()
+                .enumerate()
+                .any(|(other_index, other)| other_index != index && conference.overlaps(other));
+            let (marker, style) = if is_today && conference.is_live(&time, iso_week) {
+                ("\u{25b6} ", theme.live_conference)
+            } else if has_conflict {
+                ("! ", theme.conflict)
+            } else {
+                ("  ", theme.list_item)
+            };
+            ListItem::new(format!("{marker}{}", conference.title)).style(style)
+        });
+        let list = List::new(items).highlight_style(theme.selected_text);
         let mut state = ListState::default().with_selected(Option::from(selected_conference));
         frame.render_stateful_widget(list, area, &mut state);
+        self.list_area = area;
+        self.list_offset = state.offset();
+        self.selected_id = conferences.get(selected_conference).map(|c| c.id);
     }
 
-    fn handle_view_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
-        if key.code == KeyCode::Char('e') {
-            let (day, conf) = self.selector.selected();
-            // TODO: shouldn't be allowed to edit a conference that doesn't exist
-            self.mode = Mode::Edit(ConferenceEditForm::new(
-                self.schedule.borrow().get_day(day)[conf].clone().into(),
+    /// Rebuilds the selector for the current schedule and selects the conference with `id`.
+    fn select_conference(&mut self, id: Uuid) {
+        let position = self.schedule.borrow().position(id);
+        self.selector = Selector2D::new(self.schedule.borrow().get_conference_count_by_day());
+        if let Some((day, index)) = position {
+            self.selector.select(day, index);
+        }
+    }
+
+    fn render_details(&self, frame: &mut Frame, area: Rect) {
+        let (day, conf) = self.selector.selected();
+        let schedule = self.schedule.borrow();
+        let text = match schedule.get_day(day).get(conf) {
+            Some(conference) => self.conference_details(conference, (day, conf)),
+            None => vec![Line::from("No conference selected")],
+        };
+        let details = Paragraph::new(text)
+            .style(theme::current().text)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Details"));
+        frame.render_widget(details, area);
+    }
+
+    fn conference_details(
+        &self,
+        conference: &Conference,
+        position: (usize, usize),
+    ) -> Vec<Line<'static>> {
+        let theme = theme::current();
+        let none = "\u{2014}".to_string();
+        let password = match (&conference.password, &conference.password_id) {
+            (None, Some(_)) => "(vault locked)".to_string(),
+            (None, None) => none.clone(),
+            (Some(password), _) if self.revealed_password == Some(position) => password.clone(),
+            (Some(_), _) => PASSWORD_MASK.to_string().repeat(MASKED_PASSWORD_LENGTH),
+        };
+        let tags = if conference.tags.is_empty() {
+            none.clone()
+        } else {
+            conference.tags.join(", ")
+        };
+        let last_joined = self
+            .history
+            .borrow()
+            .iter()
+            .rfind(|record| record.is_for(conference))
+            .map_or("never".to_string(), |record| {
+                record.joined_at.format("%a %d %b %H:%M").to_string()
+            });
+        let mut lines = vec![
+            Line::from(format!(
+                "Time: {}\u{2013}{}",
+                conference.start_time, conference.end_time
+            )),
+            Line::from(format!("Week: {}", conference.week.as_str())),
+        ];
+        match MeetingLink::parse(&conference.link) {
+            Ok(meeting) => {
+                lines.push(Line::from(format!("Link: {}", conference.link)));
+                lines.push(Line::from(format!("Platform: {}", meeting.platform)));
+                lines.push(Line::from(format!(
+                    "Meeting ID: {}",
+                    meeting.meeting_id.unwrap_or(none.clone())
+                )));
+                if meeting.passcode.is_some() {
+                    lines.push(Line::from("Passcode: in link"));
+                }
+            }
+            Err(_) if conference.link.is_empty() => {
+                lines.push(Line::from(format!("Link: {none}")));
+            }
+            Err(_) => lines.push(Line::from(vec![
+                Span::raw("Link: "),
+                Span::styled(
+                    format!("{} (not a valid URL)", conference.link),
+                    theme.notification_warning,
+                ),
+            ])),
+        }
+        lines.extend([
+            Line::from(format!("Password: {password}")),
+            Line::from(format!(
+                "Autostart: {}",
+                if conference.autostart_permission {
+                    "On"
+                } else {
+                    "Off"
+                }
+            )),
+            Line::from(format!("Tags: {tags}")),
+            Line::from(format!("Last joined: {last_joined}")),
+        ]);
+        lines
+    }
+
+    pub fn commands(&self) -> Vec<Command> {
+        match self.mode {
+            Mode::View => vec![
+                Command::new("Edit selected conference", Action::EditSelected),
+                Command::new("Add conference", Action::AddConference),
+                Command::new("Search conferences", Action::OpenSearch),
+                Command::new("Show or hide password", Action::TogglePasswordVisibility),
+                Command::new("Join selected conference", Action::JoinSelected),
+                Command::new("Copy link", Action::CopyLink),
+                Command::new("Copy password", Action::CopyPassword),
+                Command::new("Select previous conference", Action::SelectPrev),
+                Command::new("Select next conference", Action::SelectNext),
+                Command::new("Previous day", Action::PrevDay),
+                Command::new("Next day", Action::NextDay),
+            ],
+            Mode::Edit(_) | Mode::Add(_) => ConferenceEditForm::commands(),
+            Mode::Search(_) => Vec::new(),
+        }
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        let key = |code| KeyEvent::from(code);
+        match self.mode {
+            Mode::Search(_) => vec![
+                KeyHint::new(vec![key(KeyCode::Up), key(KeyCode::Down)], "Select result"),
+                KeyHint::new(vec![key(KeyCode::Enter)], "Jump to conference"),
+                KeyHint::new(vec![key(KeyCode::Esc)], "Cancel search"),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn edit_selected(&mut self) -> Option<Action> {
+        let (day, conf) = self.selector.selected();
+        let conference = self.schedule.borrow().get_day(day).get(conf).cloned()?;
+        self.mode = Mode::Edit(ConferenceEditForm::new(conference.into()));
+        Some(Action::ChangeMode(AppMode::Edit))
+    }
+
+    fn handle_view_mouse_event(&mut self, mouse: MouseEvent) -> Option<Action> {
+        let position = Position::new(mouse.column, mouse.row);
+        let in_list = self.list_area.contains(position);
+        match mouse.kind {
+            MouseEventKind::ScrollUp if in_list => self.selector.move_left(),
+            MouseEventKind::ScrollDown if in_list => self.selector.move_right(),
+            MouseEventKind::Down(MouseButton::Left) if in_list => {
+                let (day, _) = self.selector.selected();
+                let index = self.list_offset + (position.y - self.list_area.y) as usize;
+                if index >= self.schedule.borrow().get_day(day).len() {
+                    return None;
+                }
+                self.selector.select(day, index);
+                let now = Instant::now();
+                let is_double_click = self.last_click.is_some_and(|(time, clicked)| {
+                    clicked == (day, index) && now - time <= DOUBLE_CLICK_INTERVAL
+                });
+                if is_double_click {
+                    self.last_click = None;
+                    return self.edit_selected();
+                }
+                self.last_click = Some((now, (day, index)));
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let tabs = self.tabs_area.inner(Margin::new(1, 1));
+                if tabs.contains(position) {
+                    let day = ((position.x - tabs.x) / DAY_TAB_WIDTH) as usize;
+                    if day < DAY_NAMES.len() {
+                        self.selector.select(day, 0);
+                    }
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn update_view(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::EditSelected => return self.edit_selected(),
+            Action::AddConference => {
+                self.mode = Mode::Add(ConferenceEditForm::new(None));
+                return Some(Action::ChangeMode(AppMode::Edit));
+            }
+            Action::OpenSearch => {
+                self.mode =
+                    Mode::Search(Box::new(ConferenceSearch::new(Rc::clone(&self.schedule))));
+                return Some(Action::ChangeMode(AppMode::Search));
+            }
+            Action::TogglePasswordVisibility => {
+                let selected = self.selector.selected();
+                self.revealed_password =
+                    (self.revealed_password != Some(selected)).then_some(selected);
+            }
+            Action::JoinSelected => {
+                let (day, conf) = self.selector.selected();
+                if conf < self.schedule.borrow().get_day(day).len() {
+                    return Some(Action::Join(self.schedule.borrow().get_day(day)[conf].id));
+                }
+            }
+            Action::CopyLink => return Some(self.copy_selected(false)),
+            Action::CopyPassword => return Some(self.copy_selected(true)),
+            Action::SelectPrev => self.selector.move_left(),
+            Action::SelectNext => self.selector.move_right(),
+            Action::PrevDay => self.selector.move_up(),
+            Action::NextDay => self.selector.move_down(),
+            _ => (),
+        };
+        None
+    }
+
+    /// Copies the link or the password of the selected conference, returning a notification
+    /// saying what was copied.
+    fn copy_selected(&self, password: bool) -> Action {
+        let (day, conf) = self.selector.selected();
+        let schedule = self.schedule.borrow();
+        let Some(conference) = schedule.get_day(day).get(conf) else {
+            return Action::Notify(Notification::new(
+                NotificationLevel::Warning,
+                "No conference selected",
             ));
-            return Ok(Some(Action::ChangeMode(AppMode::Edit)));
-        } else if key.code == KeyCode::Char('+') {
-            self.mode = Mode::Add(ConferenceEditForm::new(None));
-            return Ok(Some(Action::ChangeMode(AppMode::Edit)));
+        };
+        let (what, text) = if password {
+            if conference.password.is_none() && conference.password_id.is_some() {
+                return Action::Notify(Notification::new(
+                    NotificationLevel::Warning,
+                    "Unlock the password vault to copy the password",
+                ));
+            }
+            (
+                "password",
+                conference.password.as_deref().unwrap_or_default(),
+            )
         } else {
-            match key.code {
-                KeyCode::Up => self.selector.move_left(),
-                KeyCode::Down => self.selector.move_right(),
-                KeyCode::Left => self.selector.move_up(),
-                KeyCode::Right => self.selector.move_down(),
-                _ => (),
-            };
+            ("link", conference.link.as_str())
+        };
+        if text.is_empty() {
+            return Action::Notify(Notification::new(
+                NotificationLevel::Warning,
+                format!("{} has no {what}", conference.title),
+            ));
+        }
+        let command = self.config.config.clipboard_command.as_deref();
+        match clipboard::copy(text, command) {
+            Ok(()) => Action::Notify(Notification::new(
+                NotificationLevel::Info,
+                format!("Copied the {what} of {}", conference.title),
+            )),
+            Err(err) => Action::Notify(Notification::new(
+                NotificationLevel::Error,
+                format!("Failed to copy the {what}: {err}"),
+            )),
+        }
+    }
+
+    /// Warns that the link of a saved conference will not open, as it is not a URL.
+    fn warn_if_link_invalid(&self, conference: &Conference) -> color_eyre::Result<()> {
+        if conference.link.is_empty() || MeetingLink::parse(&conference.link).is_ok() {
+            return Ok(());
+        }
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Notify(Notification::new(
+                NotificationLevel::Warning,
+                format!("The link of {} is not a valid URL", conference.title),
+            )))?;
         }
-        Ok(None)
+        Ok(())
+    }
+
+    /// Asks for the schedule to be saved after it was changed.
+    fn request_save(&self) -> color_eyre::Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::SaveSchedule)?;
+        }
+        Ok(())
     }
 }
 
 impl Component for SchedulePage {
-    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        if action == Action::ScheduleReloaded {
+            let (day, conf) = self.selector.selected();
+            let selected = self.selected_id;
+            self.selector = Selector2D::new(self.schedule.borrow().get_conference_count_by_day());
+            self.selector.select(day, conf);
+            // follow the selected conference if it moved
+            if let Some(id) = selected {
+                self.select_conference(id);
+            }
+            self.revealed_password = None;
+            return Ok(None);
+        }
         match &mut self.mode {
-            Mode::View => Ok(self.handle_view_key_event(key)?),
-            Mode::Edit(form) => match key.code {
-                KeyCode::Esc => {
-                    let (day, conf) = self.selector.selected();
+            Mode::View => Ok(self.update_view(action)),
+            Mode::Edit(form) => match action {
+                Action::CloseForm => {
+                    let conference = form.get_conference();
+                    let id = conference.id;
+                    let title = conference.title.clone();
+                    self.mode = Mode::View;
+                    // it may have been removed by another process in the meantime, which is
+                    // not undone by bringing it back
+                    if self.schedule.borrow().get_conference(id).is_none() {
+                        if let Some(tx) = &self.command_tx {
+                            tx.send(Action::Notify(Notification::new(
+                                NotificationLevel::Warning,
+                                format!("{title} was removed elsewhere, the changes were dropped"),
+                            )))?;
+                        }
+                        return Ok(Some(Action::ChangeMode(AppMode::Schedule)));
+                    }
+                    self.warn_if_link_invalid(&conference)?;
                     self.schedule
                         .borrow_mut()
-                        .update_conference(day, conf, form.get_conference())
-                        .expect("Failed to update conference, conference not found");
+                        .update_conference(conference)
+                        .expect("the conference should exist");
+                    self.select_conference(id);
+                    self.request_save()?;
+                    Ok(Some(Action::ChangeMode(AppMode::Schedule)))
+                }
+                _ => Ok(form.update(action)?),
+            },
+            Mode::Add(form) => match action {
+                Action::CloseForm => {
+                    let (day, _) = self.selector.selected();
+                    let conference = form.get_conference();
+                    let id = conference.id;
+                    self.warn_if_link_invalid(&conference)?;
+                    self.schedule.borrow_mut().add_conference(day, conference);
                     self.mode = Mode::View;
+                    self.select_conference(id);
+                    self.request_save()?;
                     Ok(Some(Action::ChangeMode(AppMode::Schedule)))
                 }
-                _ => Ok(form.handle_key_event(key)?),
+                _ => Ok(form.update(action)?),
             },
-            Mode::Add(form) => match key.code {
+            Mode::Search(_) => Ok(None),
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        match &mut self.mode {
+            Mode::View => Ok(None),
+            Mode::Edit(form) | Mode::Add(form) => Ok(form.handle_key_event(key)?),
+            Mode::Search(search) => match key.code {
                 KeyCode::Esc => {
-                    let (day, _) = self.selector.selected();
-                    self.schedule
-                        .borrow_mut()
-                        .add_conference(day, form.get_conference());
                     self.mode = Mode::View;
-                    self.selector =
-                        Selector2D::new(self.schedule.borrow().get_conference_count_by_day());
                     Ok(Some(Action::ChangeMode(AppMode::Schedule)))
                 }
-                _ => Ok(form.handle_key_event(key)?),
+                KeyCode::Enter => {
+                    if let Some((day, conf)) = search.selected() {
+                        self.selector.select(day, conf);
+                    }
+                    self.mode = Mode::View;
+                    Ok(Some(Action::ChangeMode(AppMode::Schedule)))
+                }
+                _ => Ok(search.handle_key_event(key)?),
             },
         }
     }
 
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        match &mut self.mode {
+            Mode::View => Ok(None),
+            Mode::Edit(form) | Mode::Add(form) => Ok(form.handle_paste(text)?),
+            Mode::Search(search) => Ok(search.handle_paste(text)?),
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+        match &mut self.mode {
+            Mode::View => Ok(self.handle_view_mouse_event(mouse)),
+            Mode::Edit(form) | Mode::Add(form) => Ok(form.handle_mouse_event(mouse)?),
+            Mode::Search(search) => Ok(search.handle_mouse_event(mouse)?),
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
         match &mut self.mode {
             Mode::View => {
                 let layout: [Rect; 2] =
                     Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+                let [list_area, details_area] =
+                    Layout::horizontal([Constraint::Fill(1), Constraint::Length(DETAILS_WIDTH)])
+                        .areas(layout[1]);
                 self.render_days(frame, layout[0]);
-                self.render_conferences(frame, layout[1]);
+                self.render_conferences(frame, list_area);
+                self.render_details(frame, details_area);
             }
             Mode::Edit(form) => form.draw(frame, area)?,
             Mode::Add(form) => form.draw(frame, area)?,
+            Mode::Search(search) => search.draw(frame, area)?,
         }
         Ok(())
     }
@@ -0,0 +1,170 @@
+use super::DAY_NAMES;
+use crate::action::Action;
+use crate::entities::{Conference, Schedule};
+use crate::theme;
+use crate::ui::input::fields::{InputField, StrInputField};
+use crate::ui::Component;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use ratatui::Frame;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct SearchResult {
+    day: usize,
+    index: usize,
+    score: i64,
+}
+
+pub struct ConferenceSearch {
+    schedule: Rc<RefCell<Schedule>>,
+    query: StrInputField,
+    matcher: SkimMatcherV2,
+    results: Vec<SearchResult>,
+    selected: usize,
+}
+
+impl ConferenceSearch {
+    pub fn new(schedule: Rc<RefCell<Schedule>>) -> Self {
+        let mut query = StrInputField::new(Some("Search".into()), 50, None);
+        query.set_cursor_visibility(true);
+        let mut search = Self {
+            schedule,
+            query,
+            matcher: SkimMatcherV2::default().ignore_case(),
+            results: Vec::new(),
+            selected: 0,
+        };
+        search.refresh_results();
+        search
+    }
+
+    /// Returns the `(day, index)` of the currently highlighted conference.
+    pub fn selected(&self) -> Option<(usize, usize)> {
+        self.results
+            .get(self.selected)
+            .map(|result| (result.day, result.index))
+    }
+
+    fn score(&self, conference: &Conference, query: &str) -> Option<i64> {
+        if query.is_empty() {
+            return Some(0);
+        }
+        std::iter::once(conference.title.as_str())
+            .chain(conference.link_host())
+            .chain(conference.tags.iter().map(String::as_str))
+            .filter_map(|choice| self.matcher.fuzzy_match(choice, query))
+            .max()
+    }
+
+    fn refresh_results(&mut self) {
+        let query = self.query.get_value();
+        let schedule = self.schedule.borrow();
+        let mut results: Vec<SearchResult> = (0..DAY_NAMES.len())
+            .flat_map(|day| {
+                schedule
+                    .get_day(day)
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, conference)| {
+                        self.score(conference, &query).map(|score| SearchResult {
+                            day,
+                            index,
+                            score,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // results are grouped by weekday, best matches first within a day
+        results.sort_by(|a, b| a.day.cmp(&b.day).then(b.score.cmp(&a.score)));
+        drop(schedule);
+        self.results = results;
+        self.selected = 0;
+    }
+
+    fn select_prev(&mut self) {
+        if !self.results.is_empty() {
+            self.selected = match self.selected {
+                0 => self.results.len() - 1,
+                _ => self.selected - 1,
+            };
+        }
+    }
+
+    fn select_next(&mut self) {
+        if !self.results.is_empty() {
+            self.selected = (self.selected + 1) % self.results.len();
+        }
+    }
+
+    fn render_results(&self, frame: &mut Frame, area: Rect) {
+        let schedule = self.schedule.borrow();
+        let mut items = Vec::new();
+        let mut selected_item = None;
+        let mut current_day = None;
+        for (position, result) in self.results.iter().enumerate() {
+            if current_day != Some(result.day) {
+                current_day = Some(result.day);
+                items.push(
+                    ListItem::new(DAY_NAMES[result.day])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                );
+            }
+            if position == self.selected {
+                selected_item = Some(items.len());
+            }
+            let conference = &schedule.get_day(result.day)[result.index];
+            items.push(ListItem::new(format!(
+                "  {}  {}",
+                conference.start_time, conference.title
+            )));
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Results"))
+            .highlight_style(theme::current().selected_text);
+        let mut state = ListState::default().with_selected(selected_item);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+impl Component for ConferenceSearch {
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.select_prev(),
+            KeyCode::Down => self.select_next(),
+            _ => {
+                self.query.handle_key_event(key)?;
+                self.refresh_results();
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        self.query.handle_paste(text)?;
+        self.refresh_results();
+        Ok(None)
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.select_prev(),
+            MouseEventKind::ScrollDown => self.select_next(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let [query_area, results_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        self.query.draw(frame, query_area)?;
+        self.render_results(frame, results_area);
+        Ok(())
+    }
+}
@@ -1,45 +1,272 @@
 use crate::action::Action;
 use crate::entities::Settings;
-use crate::theme::THEME;
-use crate::ui::components::Selector;
-use crate::ui::Component;
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::layout::Rect;
-use ratatui::widgets::{List, ListItem, ListState};
+use crate::persistence::secrets::SecretBackend;
+use crate::theme::{self, ThemeName};
+use crate::ui::input::fields::{CarouselInputField, InputField, IntInputField};
+use crate::ui::input::forms::Form;
+use crate::ui::{Command, Component};
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 use std::cell::RefCell;
 use std::rc::Rc;
+use strum::IntoEnumIterator;
+
+const FIELD_WIDTH: u16 = 40;
+const MAX_EARLY_JOIN_MINUTES: u32 = 120;
+const ON: &str = "On";
+const OFF: &str = "Off";
+
+/// An editable `Settings` field. Every option gets its own row in the form, so adding a
+/// setting only takes a new entry in [`OPTIONS`].
+struct SettingOption {
+    label: &'static str,
+    description: &'static str,
+    field: fn(&'static str, &Settings) -> Box<dyn InputField>,
+    apply: fn(&mut Settings, &str),
+    // applied only once the field is no longer being edited, instead of on every change
+    on_confirm: bool,
+}
+
+const OPTIONS: [SettingOption; 5] = [
+    SettingOption {
+        label: "Autostart",
+        description: "Join conferences automatically when they start.",
+        field: |label, settings| {
+            Box::new(CarouselInputField::new(
+                Some(label.into()),
+                vec![OFF.into(), ON.into()],
+                settings.autostart.into(),
+            ))
+        },
+        apply: |settings, value| settings.autostart = value == ON,
+        on_confirm: false,
+    },
+    SettingOption {
+        label: "Join early (minutes)",
+        description: "How many minutes before its start a conference is joined when autostart \
+            is on.",
+        field: |label, settings| {
+            Box::new(IntInputField::new(
+                Some(label.into()),
+                MAX_EARLY_JOIN_MINUTES,
+                Some(settings.early_join_minutes.into()),
+            ))
+        },
+        apply: |settings, value| settings.early_join_minutes = value.parse().unwrap_or_default(),
+        on_confirm: true,
+    },
+    SettingOption {
+        label: "Theme",
+        description: "Colors used throughout the app. Styles from the config file are applied \
+            on top of the theme, and setting NO_COLOR forces the \"No color\" theme.",
+        field: |label, settings| {
+            Box::new(CarouselInputField::new(
+                Some(label.into()),
+                ThemeName::iter().map(|name| name.to_string()).collect(),
+                ThemeName::iter()
+                    .position(|name| name == settings.theme)
+                    .unwrap_or_default(),
+            ))
+        },
+        apply: |settings, value| {
+            settings.theme = ThemeName::from_label(value)
+                .expect("Theme input field should always give a valid theme")
+        },
+        on_confirm: false,
+    },
+    SettingOption {
+        label: "Mouse",
+        description: "Click tabs, conferences and fields, scroll lists and double-click a \
+            conference to edit it. Can also be turned on for a single run with --mouse.",
+        field: |label, settings| {
+            Box::new(CarouselInputField::new(
+                Some(label.into()),
+                vec![OFF.into(), ON.into()],
+                settings.mouse.into(),
+            ))
+        },
+        apply: |settings, value| settings.mouse = value == ON,
+        on_confirm: false,
+    },
+    SettingOption {
+        label: "Password storage",
+        description: "Where conference passwords are kept. The encrypted vault asks for its \
+            passphrase once per session, while a plain file can be read by anyone with access \
+            to it. Saved passwords are moved over once the choice is confirmed with Enter.",
+        field: |label, settings| {
+            Box::new(CarouselInputField::new(
+                Some(label.into()),
+                SecretBackend::iter()
+                    .map(|backend| backend.to_string())
+                    .collect(),
+                SecretBackend::iter()
+                    .position(|backend| backend == settings.secret_store)
+                    .unwrap_or_default(),
+            ))
+        },
+        apply: |settings, value| {
+            settings.secret_store = SecretBackend::from_label(value)
+                .expect("Password storage input field should always give a valid backend")
+        },
+        on_confirm: true,
+    },
+];
 
 pub struct SettingsPage {
-    selector: Selector,
+    form: Form,
     settings: Rc<RefCell<Settings>>,
 }
 
 impl SettingsPage {
     pub fn new(settings: Rc<RefCell<Settings>>) -> Self {
+        let field_layout: Vec<Vec<(Box<dyn InputField>, u16)>> = OPTIONS
+            .iter()
+            .map(|option| {
+                vec![(
+                    (option.field)(option.label, &settings.borrow()),
+                    FIELD_WIDTH,
+                )]
+            })
+            .collect();
         Self {
-            selector: Selector::new(3, 0),
+            form: Form::new(field_layout),
             settings,
         }
     }
+
+    pub fn commands(&self) -> Vec<Command> {
+        Form::commands()
+    }
+
+    /// Returns the row of the field being edited.
+    fn editing_row(&self) -> Option<usize> {
+        self.form.is_editing().then(|| self.form.selected().0)
+    }
+
+    /// Applies the values currently entered in the form to the settings, asking for them to
+    /// be saved if anything changed. `edited` is the row that was being edited before the
+    /// input, whose value is confirmed if editing it stopped.
+    fn apply_input(&mut self, edited: Option<usize>) -> Option<Action> {
+        let confirmed = edited.filter(|&row| self.editing_row() != Some(row));
+        let mut settings = self.settings.borrow_mut();
+        let previous = settings.clone();
+        for (index, (option, row)) in OPTIONS.iter().zip(self.form.get_input()).enumerate() {
+            if !option.on_confirm || confirmed == Some(index) {
+                (option.apply)(&mut settings, &row[0]);
+            }
+        }
+        if settings.theme != previous.theme {
+            theme::set_theme(settings.theme);
+        }
+        (*settings != previous).then_some(Action::SaveSettings)
+    }
 }
 
 impl Component for SettingsPage {
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
-        match key.code {
-            KeyCode::Up => self.selector.prev(),
-            KeyCode::Down => self.selector.next(),
-            _ => (),
-        };
-        Ok(None)
+        let edited = self.editing_row();
+        let action = self.form.handle_key_event(key)?;
+        Ok(action.or(self.apply_input(edited)))
+    }
+
+    fn handle_paste(&mut self, text: String) -> color_eyre::Result<Option<Action>> {
+        let edited = self.editing_row();
+        let action = self.form.handle_paste(text)?;
+        Ok(action.or(self.apply_input(edited)))
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+        let edited = self.editing_row();
+        let action = self.form.handle_mouse_event(mouse)?;
+        Ok(action.or(self.apply_input(edited)))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        let edited = self.editing_row();
+        let action = self.form.update(action)?;
+        Ok(action.or(self.apply_input(edited)))
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
-        let _ = self.settings; // to appease clippy
-        let items = ["1", "2", "3"].map(ListItem::new);
-        let list = List::new(items).highlight_style(THEME.selected_text);
-        let mut state = ListState::default().with_selected(Option::from(self.selector.index));
-        frame.render_stateful_widget(list, area, &mut state);
+        let [form_area, description_area] =
+            Layout::horizontal([Constraint::Length(FIELD_WIDTH), Constraint::Fill(1)])
+                .spacing(1)
+                .areas(area);
+        self.form.draw(frame, form_area)?;
+
+        let (row, _) = self.form.selected();
+        let option = &OPTIONS[row];
+        let description = Paragraph::new(option.description)
+            .style(theme::current().text)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(option.label));
+        frame.render_widget(description, description_area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_password_storage_changes_on_confirm() -> color_eyre::Result<()> {
+        let settings = Rc::new(RefCell::new(Settings::default()));
+        let initial = settings.borrow().secret_store;
+        let mut page = SettingsPage::new(Rc::clone(&settings));
+        let row = OPTIONS
+            .iter()
+            .position(|option| option.label == "Password storage")
+            .unwrap();
+        for _ in 0..row {
+            page.update(Action::FocusDown)?;
+        }
+
+        assert_eq!(page.update(Action::ToggleField)?, None);
+        let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(page.handle_key_event(right)?, None);
+        assert_eq!(settings.borrow().secret_store, initial);
+
+        assert_eq!(
+            page.update(Action::ToggleField)?,
+            Some(Action::SaveSettings)
+        );
+        assert_ne!(settings.borrow().secret_store, initial);
+        Ok(())
+    }
+
+    #[test]
+    fn test_early_join_minutes_change_on_confirm() -> color_eyre::Result<()> {
+        let settings = Rc::new(RefCell::new(Settings {
+            early_join_minutes: 5,
+            ..Settings::default()
+        }));
+        let mut page = SettingsPage::new(Rc::clone(&settings));
+        let row = OPTIONS
+            .iter()
+            .position(|option| option.label == "Join early (minutes)")
+            .unwrap();
+        for _ in 0..row {
+            page.update(Action::FocusDown)?;
+        }
+
+        page.update(Action::ToggleField)?;
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(page.handle_key_event(backspace)?, None);
+        assert_eq!(settings.borrow().early_join_minutes, 5);
+        page.handle_paste("10".into())?;
+        assert_eq!(settings.borrow().early_join_minutes, 5);
+
+        assert_eq!(
+            page.update(Action::ToggleField)?,
+            Some(Action::SaveSettings)
+        );
+        assert_eq!(settings.borrow().early_join_minutes, 10);
         Ok(())
     }
 }
@@ -1,9 +1,14 @@
-pub fn center_text(text: &str, width: usize, pad_with: char) -> String {
-    if text.len() > width {
-        panic!("Text to be centered cannot be longer than the width.");
-    }
+use std::borrow::Cow;
+
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-    let total_padding = width - text.len();
+/// Pads `text` on both sides to fill `width` columns, shortening it with an ellipsis if it
+/// is too wide.
+pub fn center_text(text: &str, width: usize, pad_with: char) -> String {
+    let text = truncate_to_width(text, width);
+    let total_padding = width.saturating_sub(text.width());
     let left_padding = total_padding / 2;
     let right_padding = total_padding - left_padding;
 
@@ -14,3 +19,55 @@ pub fn center_text(text: &str, width: usize, pad_with: char) -> String {
         pad_with.to_string().repeat(right_padding)
     )
 }
+
+/// Cuts `text` down to at most `width` columns, ending it with an ellipsis if anything had
+/// to be removed.
+pub fn truncate_to_width(text: &str, width: usize) -> Cow<'_, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+    let mut used_columns = 0;
+    let mut truncated: String = text
+        .graphemes(true)
+        .take_while(|grapheme| {
+            used_columns += grapheme.width();
+            used_columns < width
+        })
+        .collect();
+    if width > 0 {
+        truncated.push('\u{2026}');
+    }
+    Cow::Owned(truncated)
+}
+
+/// Returns a `width` x `height` rectangle centered in `area`, clamped to its size.
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_center_text() {
+        assert_eq!(center_text("Dark", 8, ' '), "  Dark  ");
+        assert_eq!(center_text("Тёмная", 9, '-'), "-Тёмная--");
+        assert_eq!(center_text("会議", 6, ' '), " 会議 ");
+    }
+
+    #[test]
+    fn test_center_text_too_wide() {
+        assert_eq!(center_text("High contrast", 6, ' '), "High \u{2026}");
+        assert_eq!(center_text("会議会議", 6, ' '), "会議\u{2026} ");
+        assert_eq!(center_text("anything", 0, ' '), "");
+    }
+}